@@ -20,6 +20,8 @@ use metro_m0::{CorePeripherals, Peripherals};
 
 extern crate hd44780_driver;
 
+use hd44780_driver::delay::Eh0Delay;
+use hd44780_driver::digital::Eh0Pin;
 use hd44780_driver::HD44780;
 
 extern crate embedded_hal;
@@ -37,35 +39,36 @@ fn main() {
     );
 
     let mut pins = metro_m0::pins(peripherals.PORT);
-    let delay = Delay::new(core.SYST, &mut clocks);
+    // The HAL is on embedded-hal 0.2, so its pins and delay are wrapped
+    let mut delay = Eh0Delay(Delay::new(core.SYST, &mut clocks));
 
     let mut lcd = HD44780::new_4bit(
     
-        pins.d4.into_open_drain_output(&mut pins.port), // Register Select pin
-        pins.d3.into_open_drain_output(&mut pins.port), // Enable pin
+        Eh0Pin(pins.d4.into_open_drain_output(&mut pins.port)), // Register Select pin
+        Eh0Pin(pins.d3.into_open_drain_output(&mut pins.port)), // Enable pin
 
-        pins.d9.into_open_drain_output(&mut pins.port),  // d4
-        pins.d10.into_open_drain_output(&mut pins.port), // d5
-        pins.d11.into_open_drain_output(&mut pins.port), // d6
-        pins.d12.into_open_drain_output(&mut pins.port), // d7
+        Eh0Pin(pins.d9.into_open_drain_output(&mut pins.port)),  // d4
+        Eh0Pin(pins.d10.into_open_drain_output(&mut pins.port)), // d5
+        Eh0Pin(pins.d11.into_open_drain_output(&mut pins.port)), // d6
+        Eh0Pin(pins.d12.into_open_drain_output(&mut pins.port)), // d7
 
-        delay,
-    );
+        &mut delay,
+    ).unwrap();
 
     // Unshift display and set cursor to 0
-    lcd.reset(); 
+    lcd.reset(&mut delay).unwrap();
     
     // Clear existing characters
-    lcd.clear(); 
+    lcd.clear(&mut delay).unwrap();
 
     // Display the following string
-    lcd.write_str("Hello, world!");
+    lcd.write_str("Hello, world!", &mut delay).unwrap();
 
     // Move the cursor to the second line
-    lcd.set_cursor_pos(40);
+    lcd.set_cursor_pos(40, &mut delay).unwrap();
 
     // Display the following string on the second line
-    lcd.write_str("I'm on line 2!");
+    lcd.write_str("I'm on line 2!", &mut delay).unwrap();
  
     loop { }
 }
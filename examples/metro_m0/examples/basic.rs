@@ -20,6 +20,8 @@ use metro_m0::{CorePeripherals, Peripherals};
 
 extern crate hd44780_driver;
 
+use hd44780_driver::delay::Eh0Delay;
+use hd44780_driver::digital::Eh0Pin;
 use hd44780_driver::HD44780;
 
 fn main() {
@@ -35,33 +37,34 @@ fn main() {
     );
 
     let mut pins = metro_m0::pins(peripherals.PORT);
-    let delay = Delay::new(core.SYST, &mut clocks);
+    // The HAL is on embedded-hal 0.2, so its pins and delay are wrapped
+    let mut delay = Eh0Delay(Delay::new(core.SYST, &mut clocks));
 
     let mut lcd = HD44780::new_8bit(
     
-        pins.d4.into_open_drain_output(&mut pins.port), // Register Select pin
-        pins.d3.into_open_drain_output(&mut pins.port), // Enable pin
-
-        pins.d5.into_open_drain_output(&mut pins.port),  // d0
-        pins.d6.into_open_drain_output(&mut pins.port),  // d1
-        pins.d7.into_open_drain_output(&mut pins.port),  // d2
-        pins.d8.into_open_drain_output(&mut pins.port),  // d3
-        pins.d9.into_open_drain_output(&mut pins.port),  // d4
-        pins.d10.into_open_drain_output(&mut pins.port), // d5
-        pins.d11.into_open_drain_output(&mut pins.port), // d6
-        pins.d12.into_open_drain_output(&mut pins.port), // d7
-
-        delay,
-    );
+        Eh0Pin(pins.d4.into_open_drain_output(&mut pins.port)), // Register Select pin
+        Eh0Pin(pins.d3.into_open_drain_output(&mut pins.port)), // Enable pin
+
+        Eh0Pin(pins.d5.into_open_drain_output(&mut pins.port)),  // d0
+        Eh0Pin(pins.d6.into_open_drain_output(&mut pins.port)),  // d1
+        Eh0Pin(pins.d7.into_open_drain_output(&mut pins.port)),  // d2
+        Eh0Pin(pins.d8.into_open_drain_output(&mut pins.port)),  // d3
+        Eh0Pin(pins.d9.into_open_drain_output(&mut pins.port)),  // d4
+        Eh0Pin(pins.d10.into_open_drain_output(&mut pins.port)), // d5
+        Eh0Pin(pins.d11.into_open_drain_output(&mut pins.port)), // d6
+        Eh0Pin(pins.d12.into_open_drain_output(&mut pins.port)), // d7
+
+        &mut delay,
+    ).unwrap();
 
     // Unshift display and set cursor to 0
-    lcd.reset(); 
+    lcd.reset(&mut delay).unwrap();
     
     // Clear existing characters
-    lcd.clear(); 
+    lcd.clear(&mut delay).unwrap();
 
     // Display the following string
-    lcd.write_str("Hello, world!");
+    lcd.write_str("Hello, world!", &mut delay).unwrap();
  
     loop { }
 }
@@ -20,6 +20,8 @@ use metro_m0::{CorePeripherals, Peripherals};
 
 extern crate hd44780_driver;
 
+use hd44780_driver::delay::Eh0Delay;
+use hd44780_driver::digital::Eh0Pin;
 use hd44780_driver::{HD44780, DisplayMode, Display, Cursor, CursorBlink};
 
 extern crate embedded_hal;
@@ -46,33 +48,37 @@ fn main() {
     );
 
     let mut pins = metro_m0::pins(peripherals.PORT);
-    let delay = Delay::new(core.SYST, &mut clocks);
+    // The HAL is on embedded-hal 0.2, so its pins and delay are wrapped
+    let mut delay = Eh0Delay(Delay::new(core.SYST, &mut clocks));
 
     let mut lcd = HD44780::new_8bit(
     
-        pins.d4.into_open_drain_output(&mut pins.port), // Register Select pin
-        pins.d3.into_open_drain_output(&mut pins.port), // Enable pin
-
-        pins.d5.into_open_drain_output(&mut pins.port),  // d0
-        pins.d6.into_open_drain_output(&mut pins.port),  // d1
-        pins.d7.into_open_drain_output(&mut pins.port),  // d2
-        pins.d8.into_open_drain_output(&mut pins.port),  // d3
-        pins.d9.into_open_drain_output(&mut pins.port),  // d4
-        pins.d10.into_open_drain_output(&mut pins.port), // d5
-        pins.d11.into_open_drain_output(&mut pins.port), // d6
-        pins.d12.into_open_drain_output(&mut pins.port), // d7
-
-        delay,
-    );
+        Eh0Pin(pins.d4.into_open_drain_output(&mut pins.port)), // Register Select pin
+        Eh0Pin(pins.d3.into_open_drain_output(&mut pins.port)), // Enable pin
+
+        Eh0Pin(pins.d5.into_open_drain_output(&mut pins.port)),  // d0
+        Eh0Pin(pins.d6.into_open_drain_output(&mut pins.port)),  // d1
+        Eh0Pin(pins.d7.into_open_drain_output(&mut pins.port)),  // d2
+        Eh0Pin(pins.d8.into_open_drain_output(&mut pins.port)),  // d3
+        Eh0Pin(pins.d9.into_open_drain_output(&mut pins.port)),  // d4
+        Eh0Pin(pins.d10.into_open_drain_output(&mut pins.port)), // d5
+        Eh0Pin(pins.d11.into_open_drain_output(&mut pins.port)), // d6
+        Eh0Pin(pins.d12.into_open_drain_output(&mut pins.port)), // d7
+
+        &mut delay,
+    ).unwrap();
 
     //lcd.set_cursor_mode(CursorMode::Increment);
-    lcd.set_autoscroll(true);
+    lcd.set_autoscroll(true, &mut delay).unwrap();
 
-    lcd.set_display_mode(DisplayMode {
-        cursor_visible  : Cursor::Invisible,
-        cursor_blink    : CursorBlink::On,
-        display_visible : Display::On,
-    });
+    lcd.set_display_mode(
+        DisplayMode {
+            cursor_visibility: Cursor::Invisible,
+            cursor_blink: CursorBlink::On,
+            display: Display::On,
+        },
+        &mut delay,
+    ).unwrap();
 
     let string = "Hello, world! ";
 
@@ -80,7 +86,7 @@ fn main() {
     loop {
         
         for c in string.chars() {
-            lcd.write_char(c);
+            lcd.write_char(c, &mut delay).unwrap();
 
             busy_loop();
         }
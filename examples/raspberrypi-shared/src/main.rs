@@ -0,0 +1,28 @@
+//! Three 16x2 displays sharing RS and D4-D7, each with its own EN pin
+
+use linux_embedded_hal::sysfs_gpio::Direction;
+use linux_embedded_hal::{Delay, SysfsPin};
+
+use hd44780_driver::bus::SharedBusManager;
+use hd44780_driver::HD44780;
+
+fn output(number: u64) -> SysfsPin {
+    let pin = SysfsPin::new(number);
+    pin.export().unwrap();
+    pin.set_direction(Direction::Low).unwrap();
+    pin
+}
+
+fn main() {
+    let mut delay = Delay;
+
+    let manager = SharedBusManager::new(output(26), output(21), output(20), output(16), output(12));
+
+    let mut first = HD44780::new_shared(manager.bus(output(22)), &mut delay).unwrap();
+    let mut second = HD44780::new_shared(manager.bus(output(27)), &mut delay).unwrap();
+    let mut third = HD44780::new_shared(manager.bus(output(17)), &mut delay).unwrap();
+
+    first.write_str("First", &mut delay).unwrap();
+    second.write_str("Second", &mut delay).unwrap();
+    third.write_str("Third", &mut delay).unwrap();
+}
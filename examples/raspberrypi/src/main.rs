@@ -1,12 +1,13 @@
 extern crate linux_embedded_hal;
 extern crate hd44780_driver;
 
-use linux_embedded_hal::{Delay, Pin};
+use linux_embedded_hal::{Delay, SysfsPin as Pin};
 use linux_embedded_hal::sysfs_gpio::Direction;
 
 use hd44780_driver::{HD44780, DisplayMode, Cursor, CursorBlink, Display};
 
 fn main() {
+    let mut delay = Delay;
 
     let rs = Pin::new(26);
     let en = Pin::new(22);
@@ -55,21 +56,22 @@ fn main() {
         db5,
         db6,
         db7,
-        Delay,
-    );
+        &mut delay,
+    ).unwrap();
 
-    lcd.reset();
+    lcd.reset(&mut delay).unwrap();
     
-    lcd.clear();
+    lcd.clear(&mut delay).unwrap();
 
     lcd.set_display_mode(
         DisplayMode {
             display: Display::On,
             cursor_visibility: Cursor::Visible,
             cursor_blink: CursorBlink::On,
-        }
-    );
+        },
+        &mut delay,
+    ).unwrap();
     
-    lcd.write_str("Hello, world!");
+    lcd.write_str("Hello, world!", &mut delay).unwrap();
 
 }
@@ -3,11 +3,11 @@
 
 extern crate panic_halt;
 
-use core::fmt::Write;
 use cortex_m_rt::entry;
 use hal::prelude::*;
 use hal::flash::FlashExt;
 use hal::i2c::I2c;
+use hd44780_driver::delay::Eh0Delay;
 use hd44780_driver::{Cursor, CursorBlink, Display, DisplayMode, HD44780};
 
 // Connections:
@@ -29,24 +29,27 @@ fn main() -> ! {
     let mut gpiob = dp.GPIOB.split(&mut rcc.ahb);
 
     let clocks = rcc.cfgr.freeze(&mut flash.acr);
-    let delay = hal::delay::Delay::new(cp.SYST, clocks);
+    // The HAL is on embedded-hal 0.2, so its delay is wrapped
+    let mut delay = Eh0Delay(hal::delay::Delay::new(cp.SYST, clocks));
 
     let scl = gpiob.pb6.into_af4(&mut gpiob.moder, &mut gpiob.afrl);
     let sda = gpiob.pb9.into_af4(&mut gpiob.moder, &mut gpiob.afrh);
 
     let i2c = I2c::i2c1(dp.I2C1, (scl, sda), 400.khz(), clocks, &mut rcc.apb1);
 
-    let mut lcd = HD44780::new_i2c(i2c, I2C_ADDRESS, delay);
-    lcd.reset();
-    lcd.clear();
+    let mut lcd = HD44780::new_i2c(i2c, I2C_ADDRESS, &mut delay).unwrap();
+    lcd.reset(&mut delay).unwrap();
+    lcd.clear(&mut delay).unwrap();
     lcd.set_display_mode(
         DisplayMode {
             display: Display::On,
             cursor_visibility: Cursor::Visible,
             cursor_blink: CursorBlink::On,
-        }
-    );
-    let _ = lcd.write_str("Hello, world!");
+        },
+        &mut delay,
+    )
+    .unwrap();
+    let _ = lcd.write_str("Hello, world!", &mut delay);
 
     loop {}
 }
@@ -7,6 +7,8 @@ use cortex_m_rt::entry;
 use hal::gpio::GpioExt;
 use hal::flash::FlashExt;
 use hal::rcc::RccExt;
+use hd44780_driver::delay::Eh0Delay;
+use hd44780_driver::digital::Eh0Pin;
 use hd44780_driver::{Cursor, CursorBlink, Display, DisplayMode, HD44780};
 
 // Connections:
@@ -30,7 +32,8 @@ fn main() -> ! {
     let mut gpiod = dp.GPIOD.split(&mut rcc.ahb);
 
     let clocks = rcc.cfgr.freeze(&mut flash.acr);
-    let delay = hal::delay::Delay::new(cp.SYST, clocks);
+    // The HAL is on embedded-hal 0.2, so its pins and delay are wrapped
+    let mut delay = Eh0Delay(hal::delay::Delay::new(cp.SYST, clocks));
 
     let rs = gpiod.pd1.into_push_pull_output(&mut gpiod.moder, &mut gpiod.otyper);
     let en = gpiod.pd2.into_push_pull_output(&mut gpiod.moder, &mut gpiod.otyper);
@@ -39,17 +42,28 @@ fn main() -> ! {
     let b6 = gpiod.pd6.into_push_pull_output(&mut gpiod.moder, &mut gpiod.otyper);
     let b7 = gpiod.pd7.into_push_pull_output(&mut gpiod.moder, &mut gpiod.otyper);
 
-    let mut lcd = HD44780::new_4bit(rs, en, b4, b5, b6, b7, delay);
-    lcd.reset();
-    lcd.clear();
+    let mut lcd = HD44780::new_4bit(
+        Eh0Pin(rs),
+        Eh0Pin(en),
+        Eh0Pin(b4),
+        Eh0Pin(b5),
+        Eh0Pin(b6),
+        Eh0Pin(b7),
+        &mut delay,
+    )
+    .unwrap();
+    lcd.reset(&mut delay).unwrap();
+    lcd.clear(&mut delay).unwrap();
     lcd.set_display_mode(
         DisplayMode {
             display: Display::On,
             cursor_visibility: Cursor::Visible,
             cursor_blink: CursorBlink::On,
-        }
-    );
-    lcd.write_str("Hello, world!");
+        },
+        &mut delay,
+    )
+    .unwrap();
+    lcd.write_str("Hello, world!", &mut delay).unwrap();
 
     loop {}
 }
@@ -0,0 +1,203 @@
+use crate::delay::DelayNs;
+
+use crate::bus::{ActiveController, DataBus};
+use crate::digital::OutputPin;
+use crate::error::{Error, Result};
+use crate::timing::TimingConfig;
+
+/// A bus that can switch the backlight of the display, such as an I2C
+/// backpack with a backlight transistor on one of its expander pins
+///
+/// ```rust,ignore
+/// lcd.set_backlight(false)?;
+/// ```
+pub trait Backlight {
+    /// Turn the backlight on or off, keeping it that way for every following
+    /// transfer
+    fn set_backlight(&mut self, on: bool) -> Result<()>;
+}
+
+/// Wraps another bus with a backlight that is switched by its own pin
+///
+/// The backlight can be linked to the display so that turning the display
+/// off with `set_display` turns the backlight off too, and turning the
+/// display back on restores it.
+pub struct BacklightBus<B, BL> {
+    bus: B,
+    pin: BL,
+    on: bool,
+    linked: bool,
+    display_on: bool,
+}
+
+impl<B: DataBus, BL: OutputPin> BacklightBus<B, BL> {
+    pub fn from_bus(bus: B, pin: BL) -> BacklightBus<B, BL> {
+        BacklightBus {
+            bus,
+            pin,
+            on: true,
+            linked: false,
+            display_on: true,
+        }
+    }
+
+    /// Make the backlight follow the display on / off state
+    pub fn link_to_display(&mut self, linked: bool) -> Result<()> {
+        self.linked = linked;
+
+        self.apply()
+    }
+
+    /// Give back the wrapped bus and the backlight pin
+    pub fn destroy(self) -> (B, BL) {
+        (self.bus, self.pin)
+    }
+
+    fn apply(&mut self) -> Result<()> {
+        let lit = self.on && (self.display_on || !self.linked);
+
+        match lit {
+            true => self.pin.set_high(),
+            false => self.pin.set_low(),
+        }
+        .map_err(Error::pin)
+    }
+}
+
+impl<B: DataBus, BL: OutputPin> Backlight for BacklightBus<B, BL> {
+    fn set_backlight(&mut self, on: bool) -> Result<()> {
+        self.on = on;
+
+        self.apply()
+    }
+}
+
+impl<B: DataBus, BL: OutputPin> DataBus for BacklightBus<B, BL> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.bus.write(byte, data, delay)?;
+
+        // Watch for the display control command to know when the display
+        // is switched on or off
+        if !data && byte & 0b1111_1000 == 0b0000_1000 {
+            self.display_on = byte & 0b0000_0100 != 0;
+
+            if self.linked {
+                self.apply()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8> {
+        self.bus.read(data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.bus.set_timing(timing)
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        self.bus.reset_4bit(timing, delay)
+    }
+
+    fn select_row(&mut self, row: u8) {
+        self.bus.select_row(row)
+    }
+
+    fn selected_controller(&self) -> Option<ActiveController> {
+        self.bus.selected_controller()
+    }
+
+    fn select_controller(&mut self, controller: ActiveController) {
+        self.bus.select_controller(controller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::bus::FourBitBus;
+    use crate::mock::{Delay, Pin, PinLog};
+    use crate::Display;
+    use crate::HD44780;
+
+    fn backlight(log: &PinLog) -> Vec<bool> {
+        log.events()
+            .into_iter()
+            .filter(|(name, _)| *name == "bl")
+            .map(|(_, level)| level)
+            .collect()
+    }
+
+    fn lcd(log: &PinLog) -> HD44780<BacklightBus<FourBitBus<Pin, Pin, Pin, Pin, Pin, Pin>, Pin>> {
+        HD44780::new_4bit_with_backlight(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            log.pin("bl"),
+            &mut Delay,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn set_backlight_drives_the_pin() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+        assert_eq!(backlight(&log), [true]);
+
+        lcd.set_backlight(false).unwrap();
+        lcd.set_backlight(true).unwrap();
+        assert_eq!(backlight(&log), [true, false, true]);
+    }
+
+    #[test]
+    fn backlight_is_left_alone_by_display_off_unless_linked() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_display(Display::Off, &mut Delay).unwrap();
+        assert_eq!(backlight(&log), [true]);
+
+        lcd.link_backlight_to_display(true).unwrap();
+        assert_eq!(backlight(&log), [true, false]);
+
+        lcd.set_display(Display::On, &mut Delay).unwrap();
+        lcd.set_display(Display::Off, &mut Delay).unwrap();
+        assert_eq!(backlight(&log), [true, false, true, false]);
+    }
+
+    #[test]
+    fn display_on_keeps_a_backlight_that_was_turned_off() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_8bit_with_backlight(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            log.pin("bl"),
+            &mut Delay,
+        )
+        .unwrap();
+        lcd.link_backlight_to_display(true).unwrap();
+
+        lcd.set_backlight(false).unwrap();
+        lcd.set_display(Display::Off, &mut Delay).unwrap();
+        lcd.set_display(Display::On, &mut Delay).unwrap();
+        assert_eq!(backlight(&log), [true, true, false, false, false]);
+    }
+}
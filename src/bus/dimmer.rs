@@ -0,0 +1,182 @@
+use crate::delay::DelayNs;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::bus::{ActiveController, Backlight, DataBus};
+use crate::error::{Error, Result};
+use crate::timing::TimingConfig;
+
+/// A bus that can dim the backlight of the display
+///
+/// ```rust,ignore
+/// lcd.set_brightness(64)?;
+/// ```
+pub trait Brightness {
+    /// Set the backlight brightness from 0 (off) to 255 (full)
+    fn set_brightness(&mut self, level: u8) -> Result<()>;
+}
+
+/// Wraps another bus with a backlight driven from a PWM channel
+///
+/// The brightness is remembered while the backlight is off, so switching it
+/// back on with `set_backlight(true)` restores the last level.
+pub struct DimmerBus<B, P> {
+    bus: B,
+    pwm: P,
+    level: u8,
+    on: bool,
+}
+
+impl<B: DataBus, P: SetDutyCycle> DimmerBus<B, P> {
+    pub fn from_bus(bus: B, pwm: P) -> DimmerBus<B, P> {
+        DimmerBus {
+            bus,
+            pwm,
+            level: u8::MAX,
+            on: true,
+        }
+    }
+
+    /// Give back the wrapped bus and the PWM channel
+    pub fn destroy(self) -> (B, P) {
+        (self.bus, self.pwm)
+    }
+
+    fn apply(&mut self) -> Result<()> {
+        let level = match self.on {
+            true => self.level,
+            false => 0,
+        };
+
+        self.pwm
+            .set_duty_cycle_fraction(level as u16, u8::MAX as u16)
+            .map_err(|_| Error::Bus)
+    }
+}
+
+impl<B: DataBus, P: SetDutyCycle> Brightness for DimmerBus<B, P> {
+    fn set_brightness(&mut self, level: u8) -> Result<()> {
+        self.level = level;
+
+        self.apply()
+    }
+}
+
+impl<B: DataBus, P: SetDutyCycle> Backlight for DimmerBus<B, P> {
+    fn set_backlight(&mut self, on: bool) -> Result<()> {
+        self.on = on;
+
+        self.apply()
+    }
+}
+
+impl<B: DataBus, P: SetDutyCycle> DataBus for DimmerBus<B, P> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.bus.write(byte, data, delay)
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8> {
+        self.bus.read(data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.bus.set_timing(timing)
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        self.bus.reset_4bit(timing, delay)
+    }
+
+    fn select_row(&mut self, row: u8) {
+        self.bus.select_row(row)
+    }
+
+    fn selected_controller(&self) -> Option<ActiveController> {
+        self.bus.selected_controller()
+    }
+
+    fn select_controller(&mut self, controller: ActiveController) {
+        self.bus.select_controller(controller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    use embedded_hal::pwm::ErrorType;
+
+    use super::*;
+    use crate::mock::{Delay, PinLog};
+    use crate::HD44780;
+
+    /// A PWM channel with a 1000 step duty cycle that logs each duty set
+    #[derive(Clone, Default)]
+    struct Pwm(Rc<RefCell<Vec<u16>>>);
+
+    impl ErrorType for Pwm {
+        type Error = Infallible;
+    }
+
+    impl SetDutyCycle for Pwm {
+        fn max_duty_cycle(&self) -> u16 {
+            1000
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> core::result::Result<(), Infallible> {
+            self.0.borrow_mut().push(duty);
+            Ok(())
+        }
+    }
+
+    fn lcd(log: &PinLog, pwm: &Pwm) -> HD44780<impl DataBus + Backlight + Brightness> {
+        HD44780::new_4bit_with_dimmer(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            pwm.clone(),
+            &mut Delay,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn brightness_maps_onto_the_duty_cycle() {
+        let pwm = Pwm::default();
+        let mut lcd = lcd(&PinLog::new(), &pwm);
+
+        lcd.set_brightness(0).unwrap();
+        lcd.set_brightness(51).unwrap();
+        lcd.set_brightness(255).unwrap();
+        assert_eq!(*pwm.0.borrow(), [1000, 0, 200, 1000]);
+    }
+
+    #[test]
+    fn backlight_off_restores_the_previous_level() {
+        let pwm = Pwm::default();
+        let mut lcd = lcd(&PinLog::new(), &pwm);
+        lcd.set_brightness(102).unwrap();
+
+        lcd.set_backlight(false).unwrap();
+        lcd.set_backlight(true).unwrap();
+        assert_eq!(*pwm.0.borrow(), [1000, 400, 0, 400]);
+    }
+
+    #[test]
+    fn brightness_set_while_off_waits_for_backlight_on() {
+        let pwm = Pwm::default();
+        let mut lcd = lcd(&PinLog::new(), &pwm);
+
+        lcd.set_backlight(false).unwrap();
+        lcd.set_brightness(153).unwrap();
+        lcd.set_backlight(true).unwrap();
+        assert_eq!(*pwm.0.borrow(), [1000, 0, 0, 600]);
+    }
+}
@@ -0,0 +1,324 @@
+use core::cell::Cell;
+
+use crate::delay::DelayNs;
+use crate::digital::OutputPin;
+
+use crate::{
+    bus::{DataBus, FourBitBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// Which of the two controllers in a 40x4 display the next transfer goes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveController {
+    /// The controller for rows 0 and 1, on the first enable pin
+    First,
+    /// The controller for rows 2 and 3, on the second enable pin
+    Second,
+    /// Both controllers at once, such as for init or clearing the display
+    Both,
+}
+
+/// Stands in for the enable pin of a [FourBitBus](struct.FourBitBus.html),
+/// passing each edge on to the enable pin of the active controller
+struct DualEnable<EN1: OutputPin, EN2: OutputPin> {
+    en1: EN1,
+    en2: EN2,
+    active: Cell<ActiveController>,
+}
+
+impl<EN1: OutputPin, EN2: OutputPin> DualEnable<EN1, EN2> {
+    fn set(&mut self, high: bool) -> Result<()> {
+        let active = self.active.get();
+
+        if active != ActiveController::Second {
+            match high {
+                true => self.en1.set_high().map_err(Error::pin)?,
+                false => self.en1.set_low().map_err(Error::pin)?,
+            }
+        }
+
+        if active != ActiveController::First {
+            match high {
+                true => self.en2.set_high().map_err(Error::pin)?,
+                false => self.en2.set_low().map_err(Error::pin)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<EN1: OutputPin, EN2: OutputPin> embedded_hal::digital::ErrorType for DualEnable<EN1, EN2> {
+    type Error = Error;
+}
+
+impl<EN1: OutputPin, EN2: OutputPin> OutputPin for DualEnable<EN1, EN2> {
+    fn set_low(&mut self) -> Result<()> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<()> {
+        self.set(true)
+    }
+}
+
+/// A 4-bit bus to the two controllers of a 40x4 display, which share the
+/// register select and data pins but each have their own enable pin.
+///
+/// Transfers go to whichever controller was last picked with
+/// [select](#method.select).
+pub struct DualEnableBus<
+    RS: OutputPin,
+    EN1: OutputPin,
+    EN2: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+> {
+    bus: FourBitBus<RS, DualEnable<EN1, EN2>, D4, D5, D6, D7>,
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > DualEnableBus<RS, EN1, EN2, D4, D5, D6, D7>
+{
+    pub fn from_pins(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> DualEnableBus<RS, EN1, EN2, D4, D5, D6, D7> {
+        let en = DualEnable {
+            en1,
+            en2,
+            active: Cell::new(ActiveController::First),
+        };
+
+        DualEnableBus {
+            bus: FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
+        }
+    }
+
+    /// Send the following transfers to `controller`
+    pub fn select(&mut self, controller: ActiveController) {
+        self.bus.enable_pin().active.set(controller);
+    }
+
+    /// The controller transfers are currently sent to
+    pub fn selected(&self) -> ActiveController {
+        self.bus.enable_pin().active.get()
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > DataBus for DualEnableBus<RS, EN1, EN2, D4, D5, D6, D7>
+{
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.bus.write(byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.bus.set_timing(timing)
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        self.bus.reset_4bit(timing, delay)
+    }
+
+    fn select_row(&mut self, row: u8) {
+        let controller = match row {
+            0 | 1 => ActiveController::First,
+            _ => ActiveController::Second,
+        };
+        self.select(controller);
+    }
+
+    fn selected_controller(&self) -> Option<ActiveController> {
+        Some(self.selected())
+    }
+
+    fn select_controller(&mut self, controller: ActiveController) {
+        self.select(controller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{Delay, Pin, PinLog};
+    use crate::HD44780;
+
+    const DATA: [&str; 4] = ["d4", "d5", "d6", "d7"];
+
+    fn commands(log: &PinLog, en: &str) -> Vec<u8> {
+        log.bytes_4bit(en, "rs", DATA)
+            .into_iter()
+            .filter(|&(rs, _)| !rs)
+            .map(|(_, byte)| byte)
+            .collect()
+    }
+
+    fn data(log: &PinLog, en: &str) -> Vec<u8> {
+        log.bytes_4bit(en, "rs", DATA)
+            .into_iter()
+            .filter(|&(rs, _)| rs)
+            .map(|(_, byte)| byte)
+            .collect()
+    }
+
+    fn lcd(log: &PinLog) -> HD44780<DualEnableBus<Pin, Pin, Pin, Pin, Pin, Pin, Pin>> {
+        HD44780::new_dual_4bit(
+            log.pin("rs"),
+            log.pin("en1"),
+            log.pin("en2"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn init_runs_on_both_controllers() {
+        let log = PinLog::new();
+        let _lcd = lcd(&log);
+
+        let first = commands(&log, "en1");
+        let second = commands(&log, "en2");
+        assert!(!first.is_empty());
+        assert_eq!(second[..first.len()], first[..]);
+
+        // Then the second controller hides its cursor
+        assert_eq!(second[first.len()..], [0x0C]);
+    }
+
+    #[test]
+    fn cursor_follows_the_selected_controller() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+        let first = commands(&log, "en1").len();
+        let second = commands(&log, "en2").len();
+
+        lcd.set_cursor_xy(0, 2, &mut Delay).unwrap();
+        lcd.set_cursor_xy(0, 3, &mut Delay).unwrap();
+        lcd.set_cursor_xy(3, 0, &mut Delay).unwrap();
+
+        assert_eq!(commands(&log, "en1")[first..], [0x0C, 0x0E, 0x83]);
+        assert_eq!(commands(&log, "en2")[second..], [0x0E, 0x80, 0xC0, 0x0C]);
+    }
+
+    #[test]
+    fn whole_display_commands_go_to_both_controllers() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_cursor_xy(0, 2, &mut Delay).unwrap();
+        let first = commands(&log, "en1").len();
+        let second = commands(&log, "en2").len();
+
+        lcd.set_display(crate::Display::Off, &mut Delay).unwrap();
+        lcd.shift_display(crate::Direction::Left, &mut Delay)
+            .unwrap();
+        lcd.clear(&mut Delay).unwrap();
+
+        assert_eq!(commands(&log, "en1")[first..], [0x08, 0x18, 0x01, 0x0A]);
+        assert_eq!(commands(&log, "en2")[second..], [0x0A, 0x18, 0x01, 0x08]);
+        assert_eq!(lcd.bus.selected(), ActiveController::First);
+    }
+
+    #[test]
+    fn rows_map_onto_the_two_controllers() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+
+        let address = |cmd: &u8| cmd & 0x80 != 0;
+        let init = commands(&log, "en1").len();
+
+        // (column, row) to the controller's own DDRAM address
+        lcd.set_cursor_xy(0, 0, &mut Delay).unwrap();
+        lcd.set_cursor_xy(39, 1, &mut Delay).unwrap();
+        lcd.set_cursor_xy(0, 2, &mut Delay).unwrap();
+        lcd.set_cursor_xy(5, 3, &mut Delay).unwrap();
+
+        let first: Vec<u8> = commands(&log, "en1")[init..]
+            .iter()
+            .copied()
+            .filter(address)
+            .collect();
+        let second: Vec<u8> = commands(&log, "en2")[init..]
+            .iter()
+            .copied()
+            .filter(address)
+            .collect();
+        assert_eq!(first, [0x80, 0xE7]);
+        assert_eq!(second, [0x80, 0xC5]);
+    }
+
+    #[test]
+    fn writes_follow_the_cursor_row() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_cursor_xy(0, 1, &mut Delay).unwrap();
+        lcd.write_str("top", &mut Delay).unwrap();
+        lcd.set_cursor_xy(0, 3, &mut Delay).unwrap();
+        lcd.write_str("bottom", &mut Delay).unwrap();
+
+        assert_eq!(data(&log, "en1"), b"top");
+        assert_eq!(data(&log, "en2"), b"bottom");
+    }
+
+    #[test]
+    fn reinit_sets_up_both_controllers() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_cursor_xy(0, 3, &mut Delay).unwrap();
+        let first = commands(&log, "en1").len();
+        let second = commands(&log, "en2").len();
+
+        lcd.reinit(&mut Delay).unwrap();
+
+        let first = commands(&log, "en1")[first..].to_vec();
+        let second = commands(&log, "en2")[second..].to_vec();
+        assert_eq!(first[..6], second[..6]);
+        assert_eq!(first.last(), Some(&0x0C));
+        assert_eq!(second.last(), Some(&0x0E));
+        assert_eq!(lcd.bus.selected(), ActiveController::Second);
+    }
+
+    #[test]
+    fn both_controllers_can_be_selected() {
+        let log = PinLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.select_controller(ActiveController::Both);
+        lcd.write_str("x", &mut Delay).unwrap();
+
+        assert_eq!(data(&log, "en1"), b"x");
+        assert_eq!(data(&log, "en2"), b"x");
+    }
+}
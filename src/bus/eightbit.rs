@@ -1,9 +1,10 @@
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
-use embedded_hal::digital::v2::OutputPin;
+use crate::delay::DelayNs;
+use crate::digital::{InputPin, OutputPin};
 
 use crate::{
-    bus::DataBus,
+    bus::{BusHooks, DataBus, LineCharacteristics, NoPin},
     error::{Error, Result},
+    timing::TimingConfig,
 };
 
 pub struct EightBitBus<
@@ -17,8 +18,10 @@ pub struct EightBitBus<
     D5: OutputPin,
     D6: OutputPin,
     D7: OutputPin,
+    RW = NoPin,
 > {
     rs: RS,
+    rw: RW,
     en: EN,
     d0: D0,
     d1: D1,
@@ -28,6 +31,9 @@ pub struct EightBitBus<
     d5: D5,
     d6: D6,
     d7: D7,
+    hooks: BusHooks,
+    line: LineCharacteristics,
+    timing: TimingConfig,
 }
 
 impl<
@@ -57,6 +63,7 @@ impl<
     ) -> EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7> {
         EightBitBus {
             rs,
+            rw: NoPin,
             en,
             d0,
             d1,
@@ -66,9 +73,133 @@ impl<
             d5,
             d6,
             d7,
+            hooks: BusHooks::default(),
+            line: LineCharacteristics::default(),
+            timing: TimingConfig::default(),
         }
     }
 
+    /// Give back the pins in the order they were passed to
+    /// [from_pins](#method.from_pins)
+    pub fn destroy(self) -> (RS, EN, D0, D1, D2, D3, D4, D5, D6, D7) {
+        (
+            self.rs, self.en, self.d0, self.d1, self.d2, self.d3, self.d4, self.d5, self.d6,
+            self.d7,
+        )
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin + InputPin,
+        D1: OutputPin + InputPin,
+        D2: OutputPin + InputPin,
+        D3: OutputPin + InputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>
+{
+    /// Create a bus that can also read from the display through its R/W pin.
+    ///
+    /// To read, the data pins are set high and then sampled while the
+    /// display drives them, so they need to be configured as open drain
+    /// outputs (with pull-ups) that can still be read back.
+    pub fn from_pins_rw(
+        rs: RS,
+        mut rw: RW,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> Result<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>> {
+        rw.set_low().map_err(Error::pin)?;
+
+        Ok(EightBitBus {
+            rs,
+            rw,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            hooks: BusHooks::default(),
+            line: LineCharacteristics::default(),
+            timing: TimingConfig::default(),
+        })
+    }
+
+    /// Give back the pins in the order they were passed to
+    /// [from_pins_rw](#method.from_pins_rw)
+    pub fn destroy(self) -> (RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7) {
+        (
+            self.rs, self.rw, self.en, self.d0, self.d1, self.d2, self.d3, self.d4, self.d5,
+            self.d6, self.d7,
+        )
+    }
+
+    fn get_bus_bits(&mut self) -> Result<u8> {
+        let pins: [bool; 8] = [
+            self.d0.is_high().map_err(Error::pin)?,
+            self.d1.is_high().map_err(Error::pin)?,
+            self.d2.is_high().map_err(Error::pin)?,
+            self.d3.is_high().map_err(Error::pin)?,
+            self.d4.is_high().map_err(Error::pin)?,
+            self.d5.is_high().map_err(Error::pin)?,
+            self.d6.is_high().map_err(Error::pin)?,
+            self.d7.is_high().map_err(Error::pin)?,
+        ];
+
+        Ok(pins
+            .iter()
+            .enumerate()
+            .fold(0, |byte, (i, &high)| byte | ((high as u8) << i)))
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        RW,
+    > EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>
+{
+    /// Set the functions called at each step of a transfer, see
+    /// [BusHooks](struct.BusHooks.html)
+    pub fn set_hooks(&mut self, hooks: BusHooks) {
+        self.hooks = hooks;
+    }
+
+    /// Set how slowly the lines settle so the transfer timing can allow for
+    /// it, see [LineCharacteristics](struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.line = line;
+    }
+
+    /// Time in microseconds the strobe of a byte takes
+    pub(crate) fn byte_transfer_us(&self) -> u32 {
+        self.line.strobe_us(&self.timing)
+    }
+
     fn set_bus_bits(&mut self, data: u8) -> Result<()> {
         let db0: bool = (0b0000_0001 & data) != 0;
         let db1: bool = (0b0000_0010 & data) != 0;
@@ -80,51 +211,78 @@ impl<
         let db7: bool = (0b1000_0000 & data) != 0;
 
         if db0 {
-            self.d0.set_high().map_err(|_| Error)?;
+            self.d0.set_high().map_err(Error::pin)?;
         } else {
-            self.d0.set_low().map_err(|_| Error)?;
+            self.d0.set_low().map_err(Error::pin)?;
         }
 
         if db1 {
-            self.d1.set_high().map_err(|_| Error)?;
+            self.d1.set_high().map_err(Error::pin)?;
         } else {
-            self.d1.set_low().map_err(|_| Error)?;
+            self.d1.set_low().map_err(Error::pin)?;
         }
 
         if db2 {
-            self.d2.set_high().map_err(|_| Error)?;
+            self.d2.set_high().map_err(Error::pin)?;
         } else {
-            self.d2.set_low().map_err(|_| Error)?;
+            self.d2.set_low().map_err(Error::pin)?;
         }
 
         if db3 {
-            self.d3.set_high().map_err(|_| Error)?;
+            self.d3.set_high().map_err(Error::pin)?;
         } else {
-            self.d3.set_low().map_err(|_| Error)?;
+            self.d3.set_low().map_err(Error::pin)?;
         }
 
         if db4 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::pin)?;
         }
 
         if db5 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::pin)?;
         }
 
         if db6 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::pin)?;
         }
 
         if db7 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::pin)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_byte<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        if data {
+            self.rs.set_high().map_err(Error::pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::pin)?;
+        }
+
+        self.hooks.before_data_set(byte, data);
+
+        self.set_bus_bits(byte)?;
+        self.line.data_setup(delay);
+
+        self.en.set_high().map_err(Error::pin)?;
+        self.hooks.after_en_rise(byte, data);
+        self.timing.enable_pulse(delay);
+        self.line.enable_pulse(delay);
+        self.en.set_low().map_err(Error::pin)?;
+        self.line.enable_hold(delay);
+        self.hooks.after_en_fall(byte, data);
+
+        if data {
+            self.rs.set_low().map_err(Error::pin)?;
         }
 
         Ok(())
@@ -142,30 +300,187 @@ impl<
         D5: OutputPin,
         D6: OutputPin,
         D7: OutputPin,
-    > DataBus for EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>
+    > DataBus for EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, NoPin>
 {
-    fn write<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        byte: u8,
-        data: bool,
-        delay: &mut D,
-    ) -> Result<()> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_byte(byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.timing = *timing;
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin + InputPin,
+        D1: OutputPin + InputPin,
+        D2: OutputPin + InputPin,
+        D3: OutputPin + InputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > DataBus for EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>
+{
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_byte(byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.timing = *timing;
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8> {
         if data {
-            self.rs.set_high().map_err(|_| Error)?;
+            self.rs.set_high().map_err(Error::pin)?;
         } else {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::pin)?;
         }
 
-        self.set_bus_bits(byte)?;
+        self.rw.set_high().map_err(Error::pin)?;
+
+        // Let go of the data lines so the display can drive them
+        self.set_bus_bits(0xFF)?;
+        self.line.data_setup(delay);
+
+        self.en.set_high().map_err(Error::pin)?;
+        delay.delay_us(1);
+        self.line.enable_pulse(delay);
+        let byte = self.get_bus_bits();
+
+        // Drop enable and hand the data lines back even if sampling failed,
+        // so the display isn't left part way through a transfer
+        self.en.set_low().map_err(Error::pin)?;
+        self.line.enable_hold(delay);
 
-        self.en.set_high().map_err(|_| Error)?;
-        delay.delay_ms(2u8);
-        self.en.set_low().map_err(|_| Error)?;
+        self.rw.set_low().map_err(Error::pin)?;
 
         if data {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::pin)?;
         }
 
-        Ok(())
+        byte
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{BrokenInput, Delay, PinLog};
+    use crate::HD44780;
+
+    const DATA: [&str; 8] = ["d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7"];
+
+    #[test]
+    fn failed_read_still_drops_enable() {
+        let log = PinLog::new();
+        let mut bus = EightBitBus::from_pins_rw(
+            log.pin("rs"),
+            log.pin("rw"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            BrokenInput(log.pin("d7")),
+        )
+        .unwrap();
+
+        assert_eq!(
+            bus.read(false, &mut Delay),
+            Err(Error::Pin(embedded_hal::digital::ErrorKind::Other))
+        );
+
+        let last = |name| log.events().iter().rev().find(|e| e.0 == name).unwrap().1;
+        assert!(!last("en"));
+        assert!(!last("rw"));
+    }
+
+    #[test]
+    fn read_byte_samples_the_data_pins() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_8bit_rw(
+            log.pin("rs"),
+            log.pin("rw"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        // The byte, then the busy flag read that waits for the address
+        // counter to move along
+        log.respond("rw", "en", &DATA, &[0xA5, 0x00]);
+        assert_eq!(lcd.read_byte(&mut Delay), Ok(0xA5));
+
+        // R/W is left low so later writes go through
+        let events = log.events();
+        assert_eq!(
+            events.iter().rev().find(|e| e.0 == "rw"),
+            Some(&("rw", false))
+        );
+    }
+
+    #[test]
+    fn destroyed_pins_can_be_used_again() {
+        let log = PinLog::new();
+        let lcd = HD44780::new_8bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+        let init = log.latched("en", "rs", &DATA);
+
+        let (rs, en, d0, d1, d2, d3, d4, d5, d6, d7) = lcd.destroy().destroy();
+        let mut lcd =
+            HD44780::new_8bit(rs, en, d0, d1, d2, d3, d4, d5, d6, d7, &mut Delay).unwrap();
+        lcd.write_str("again", &mut Delay).unwrap();
+
+        let latched = log.latched("en", "rs", &DATA);
+        assert_eq!(latched[init.len()..init.len() * 2], init[..]);
+        assert_eq!(latched.len(), init.len() * 2 + 5);
+    }
+
+    #[test]
+    fn read_without_rw_pin_is_unsupported() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_8bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        assert_eq!(lcd.read_byte(&mut Delay), Err(Error::ReadUnsupported));
     }
 }
@@ -1,144 +1,672 @@
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
-use embedded_hal::digital::v2::OutputPin;
+use crate::delay::DelayNs;
+use crate::digital::{InputPin, OutputPin};
 
-use crate::bus::DataBus;
+use crate::bus::{
+    reset_by_nibbles, BusHooks, DataBus, LineCharacteristics, NibbleBus, NoPin, PinMap,
+};
 use crate::error::{Error, Result};
+use crate::timing::TimingConfig;
 
-pub struct FourBitBus<
-    RS: OutputPin,
-    EN: OutputPin,
-    D4: OutputPin,
-    D5: OutputPin,
-    D6: OutputPin,
-    D7: OutputPin,
-> {
+/// The register select and data pins of a 4-bit bus, and how transfers on
+/// them are clocked. The enable pin is passed in with each transfer, so
+/// displays sharing these pins can each pulse their own.
+pub(crate) struct FourBitLines<RS, D4, D5, D6, D7> {
     rs: RS,
-    en: EN,
     d4: D4,
     d5: D5,
     d6: D6,
     d7: D7,
+    pub(crate) hooks: BusHooks,
+    pub(crate) line: LineCharacteristics,
+    pub(crate) timing: TimingConfig,
+    map: PinMap,
 }
 
-impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
-    FourBitBus<RS, EN, D4, D5, D6, D7>
+impl<RS: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
+    FourBitLines<RS, D4, D5, D6, D7>
 {
-    pub fn from_pins(
+    pub(crate) fn new(
         rs: RS,
-        en: EN,
         d4: D4,
         d5: D5,
         d6: D6,
         d7: D7,
-    ) -> FourBitBus<RS, EN, D4, D5, D6, D7> {
-        FourBitBus {
+        map: PinMap,
+    ) -> FourBitLines<RS, D4, D5, D6, D7> {
+        FourBitLines {
             rs,
-            en,
             d4,
             d5,
             d6,
             d7,
+            hooks: BusHooks::default(),
+            line: LineCharacteristics::default(),
+            timing: TimingConfig::default(),
+            map,
         }
     }
 
-    fn write_lower_nibble(&mut self, data: u8) -> Result<()> {
-        let db0: bool = (0b0000_0001 & data) != 0;
-        let db1: bool = (0b0000_0010 & data) != 0;
-        let db2: bool = (0b0000_0100 & data) != 0;
-        let db3: bool = (0b0000_1000 & data) != 0;
+    /// Send the lower four bits of `nibble` with one pulse of `en`
+    pub(crate) fn write_nibble<EN: OutputPin, D: DelayNs>(
+        &mut self,
+        en: &mut EN,
+        nibble: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.set_register_select(data)?;
+        self.send_nibble(en, nibble & 0x0F, data, delay)?;
 
-        if db0 {
-            self.d4.set_high().map_err(|_| Error)?;
-        } else {
-            self.d4.set_low().map_err(|_| Error)?;
+        if data {
+            self.rs.set_low().map_err(Error::pin)?;
         }
+        Ok(())
+    }
 
-        if db1 {
-            self.d5.set_high().map_err(|_| Error)?;
-        } else {
-            self.d5.set_low().map_err(|_| Error)?;
-        }
+    /// Send `byte` as two nibbles, upper first, each with a pulse of `en`
+    pub(crate) fn write_byte<EN: OutputPin, D: DelayNs>(
+        &mut self,
+        en: &mut EN,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.set_register_select(data)?;
 
-        if db2 {
-            self.d6.set_high().map_err(|_| Error)?;
-        } else {
-            self.d6.set_low().map_err(|_| Error)?;
+        self.send_nibble(en, byte >> 4, data, delay)?;
+        self.send_nibble(en, byte & 0x0F, data, delay)?;
+
+        if data {
+            self.rs.set_low().map_err(Error::pin)?;
         }
+        Ok(())
+    }
 
-        if db3 {
-            self.d7.set_high().map_err(|_| Error)?;
+    fn set_register_select(&mut self, data: bool) -> Result<()> {
+        if data {
+            self.rs.set_high().map_err(Error::pin)
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::pin)
         }
-
-        Ok(())
     }
 
-    fn write_upper_nibble(&mut self, data: u8) -> Result<()> {
-        let db4: bool = (0b0001_0000 & data) != 0;
-        let db5: bool = (0b0010_0000 & data) != 0;
-        let db6: bool = (0b0100_0000 & data) != 0;
-        let db7: bool = (0b1000_0000 & data) != 0;
+    fn set_data_pins(&mut self, nibble: u8) -> Result<()> {
+        let pins = self.map.pins_for(nibble);
+
+        let db4: bool = (0b0001 & pins) != 0;
+        let db5: bool = (0b0010 & pins) != 0;
+        let db6: bool = (0b0100 & pins) != 0;
+        let db7: bool = (0b1000 & pins) != 0;
 
         if db4 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::pin)?;
         }
 
         if db5 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::pin)?;
         }
 
         if db6 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::pin)?;
         }
 
         if db7 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::pin)?;
         }
+
+        Ok(())
+    }
+
+    fn send_nibble<EN: OutputPin, D: DelayNs>(
+        &mut self,
+        en: &mut EN,
+        nibble: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.hooks.before_data_set(nibble, data);
+
+        self.set_data_pins(nibble)?;
+        self.line.data_setup(delay);
+
+        // Pulse the enable pin to recieve the nibble
+        en.set_high().map_err(Error::pin)?;
+        self.hooks.after_en_rise(nibble, data);
+        self.timing.enable_pulse(delay);
+        self.line.enable_pulse(delay);
+        en.set_low().map_err(Error::pin)?;
+        self.line.enable_hold(delay);
+        self.hooks.after_en_fall(nibble, data);
+
         Ok(())
     }
 }
 
+pub struct FourBitBus<
+    RS: OutputPin,
+    EN: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+    RW = NoPin,
+> {
+    lines: FourBitLines<RS, D4, D5, D6, D7>,
+    rw: RW,
+    en: EN,
+}
+
 impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
-    DataBus for FourBitBus<RS, EN, D4, D5, D6, D7>
+    FourBitBus<RS, EN, D4, D5, D6, D7>
 {
-    fn write<D: DelayUs<u16> + DelayMs<u8>>(
+    pub fn from_pins(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> FourBitBus<RS, EN, D4, D5, D6, D7> {
+        FourBitBus::from_pins_with_map(rs, en, d4, d5, d6, d7, PinMap::Straight)
+    }
+
+    /// Give back the pins in the order they were passed to
+    /// [from_pins](#method.from_pins)
+    pub fn destroy(self) -> (RS, EN, D4, D5, D6, D7) {
+        let lines = self.lines;
+        (lines.rs, self.en, lines.d4, lines.d5, lines.d6, lines.d7)
+    }
+
+    /// Create a bus whose data pins aren't wired to DB4 to DB7 in order, see
+    /// [PinMap](enum.PinMap.html)
+    pub fn from_pins_with_map(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        map: PinMap,
+    ) -> FourBitBus<RS, EN, D4, D5, D6, D7> {
+        FourBitBus {
+            lines: FourBitLines::new(rs, d4, d5, d6, d7, map),
+            rw: NoPin,
+            en,
+        }
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > FourBitBus<RS, EN, D4, D5, D6, D7, RW>
+{
+    /// Create a bus that can also read from the display through its R/W pin.
+    ///
+    /// To read, the data pins are set high and then sampled while the
+    /// display drives them, so they need to be configured as open drain
+    /// outputs (with pull-ups) that can still be read back.
+    pub fn from_pins_rw(
+        rs: RS,
+        mut rw: RW,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> Result<FourBitBus<RS, EN, D4, D5, D6, D7, RW>> {
+        rw.set_low().map_err(Error::pin)?;
+
+        Ok(FourBitBus {
+            lines: FourBitLines::new(rs, d4, d5, d6, d7, PinMap::Straight),
+            rw,
+            en,
+        })
+    }
+
+    /// Give back the pins in the order they were passed to
+    /// [from_pins_rw](#method.from_pins_rw)
+    pub fn destroy(self) -> (RS, RW, EN, D4, D5, D6, D7) {
+        let lines = self.lines;
+        (
+            lines.rs, self.rw, self.en, lines.d4, lines.d5, lines.d6, lines.d7,
+        )
+    }
+
+    /// Pulse the enable pin and sample the nibble the display puts on the
+    /// data lines while it is high
+    fn read_nibble<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8> {
+        self.en.set_high().map_err(Error::pin)?;
+        delay.delay_us(1);
+        self.lines.line.enable_pulse(delay);
+
+        let pins = self.get_data_pins();
+
+        // Drop enable even if sampling failed, so the display isn't left
+        // part way through a transfer
+        self.en.set_low().map_err(Error::pin)?;
+        self.lines.line.enable_hold(delay);
+
+        let pins = pins?
+            .iter()
+            .enumerate()
+            .fold(0, |pins, (i, &high)| pins | ((high as u8) << i));
+
+        Ok(self.lines.map.nibble_for(pins))
+    }
+
+    fn get_data_pins(&mut self) -> Result<[bool; 4]> {
+        Ok([
+            self.lines.d4.is_high().map_err(Error::pin)?,
+            self.lines.d5.is_high().map_err(Error::pin)?,
+            self.lines.d6.is_high().map_err(Error::pin)?,
+            self.lines.d7.is_high().map_err(Error::pin)?,
+        ])
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        RW,
+    > FourBitBus<RS, EN, D4, D5, D6, D7, RW>
+{
+    /// Set the functions called at each step of a transfer, see
+    /// [BusHooks](struct.BusHooks.html)
+    pub fn set_hooks(&mut self, hooks: BusHooks) {
+        self.lines.hooks = hooks;
+    }
+
+    /// Set how slowly the lines settle so the transfer timing can allow for
+    /// it, see [LineCharacteristics](struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.lines.line = line;
+    }
+
+    /// Time in microseconds the two strobes of a byte take
+    pub(crate) fn byte_transfer_us(&self) -> u32 {
+        2 * self.lines.line.strobe_us(&self.lines.timing)
+    }
+
+    pub(crate) fn enable_pin(&self) -> &EN {
+        &self.en
+    }
+
+    /// Send a single 4-bit transfer with one pulse of the enable pin, for
+    /// sequences such as the reset by instruction that don't come in whole
+    /// bytes. Only the lower four bits of `nibble` are sent.
+    ///
+    /// ```rust,ignore
+    /// bus.write_nibble(0x3, false, &mut delay)?;
+    /// ```
+    pub fn write_nibble<D: DelayNs>(
         &mut self,
-        byte: u8,
+        nibble: u8,
         data: bool,
         delay: &mut D,
     ) -> Result<()> {
-        if data {
-            self.rs.set_high().map_err(|_| Error)?;
-        } else {
-            self.rs.set_low().map_err(|_| Error)?;
-        }
+        self.lines.write_nibble(&mut self.en, nibble, data, delay)
+    }
+
+    fn write_byte<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.lines.write_byte(&mut self.en, byte, data, delay)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        RW,
+    > NibbleBus for FourBitBus<RS, EN, D4, D5, D6, D7, RW>
+{
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        FourBitBus::write_nibble(self, nibble, data, delay)
+    }
+}
+
+impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
+    DataBus for FourBitBus<RS, EN, D4, D5, D6, D7, NoPin>
+{
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_byte(byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.lines.timing = *timing;
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            FourBitBus::write_nibble(self, nibble, false, delay)
+        })
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > DataBus for FourBitBus<RS, EN, D4, D5, D6, D7, RW>
+{
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_byte(byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.lines.timing = *timing;
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            FourBitBus::write_nibble(self, nibble, false, delay)
+        })
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8> {
+        self.lines.set_register_select(data)?;
 
-        self.write_upper_nibble(byte)?;
+        self.rw.set_high().map_err(Error::pin)?;
 
-        // Pulse the enable pin to recieve the upper nibble
-        self.en.set_high().map_err(|_| Error)?;
-        delay.delay_ms(2u8);
-        self.en.set_low().map_err(|_| Error)?;
+        // Let go of the data lines so the display can drive them
+        self.lines.set_data_pins(0x0F)?;
+        self.lines.line.data_setup(delay);
 
-        self.write_lower_nibble(byte)?;
+        // Clock out both nibbles and hand the data lines back even if a read
+        // fails, so the next transfer starts on a whole byte
+        let upper_nibble = self.read_nibble(delay);
+        let lower_nibble = self.read_nibble(delay);
 
-        // Pulse the enable pin to recieve the lower nibble
-        self.en.set_high().map_err(|_| Error)?;
-        delay.delay_ms(2u8);
-        self.en.set_low().map_err(|_| Error)?;
+        self.rw.set_low().map_err(Error::pin)?;
 
         if data {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.lines.rs.set_low().map_err(Error::pin)?;
         }
-        Ok(())
+
+        Ok((upper_nibble? << 4) | lower_nibble?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{BrokenInput, Delay, PinLog};
+    use crate::{ControllerVariant, HD44780};
+
+    const DATA: [&str; 4] = ["d4", "d5", "d6", "d7"];
+
+    #[test]
+    fn read_byte_joins_both_nibbles() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_4bit_rw(
+            log.pin("rs"),
+            log.pin("rw"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        // The byte, then the busy flag read that waits for the address
+        // counter to move along
+        log.respond("rw", "en", &DATA, &[0x4, 0x8, 0x0, 0x0]);
+        assert_eq!(lcd.read_byte(&mut Delay), Ok(0x48));
+
+        // Writes still line up on whole bytes after a read
+        let before = log.bytes_4bit("en", "rs", DATA).len();
+        lcd.write_str("A", &mut Delay).unwrap();
+        let written = log.bytes_4bit("en", "rs", DATA);
+        assert_eq!(written[before], (true, b'A'));
+    }
+
+    #[test]
+    fn failed_read_still_drops_enable() {
+        let log = PinLog::new();
+        let mut bus = FourBitBus::from_pins_rw(
+            log.pin("rs"),
+            log.pin("rw"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            BrokenInput(log.pin("d7")),
+        )
+        .unwrap();
+
+        assert_eq!(
+            bus.read(true, &mut Delay),
+            Err(Error::Pin(embedded_hal::digital::ErrorKind::Other))
+        );
+
+        let last = |name| log.events().iter().rev().find(|e| e.0 == name).unwrap().1;
+        assert!(!last("en"));
+        assert!(!last("rw"));
+
+        // Both nibbles are clocked so the display stays on whole bytes
+        let pulses = log.events().iter().filter(|e| **e == ("en", true)).count();
+        assert_eq!(pulses, 2);
+    }
+
+    #[test]
+    fn init_follows_the_datasheet_nibble_sequence() {
+        let log = PinLog::new();
+        HD44780::new_4bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        let pulses = log.events().iter().filter(|&&e| e == ("en", true)).count();
+        let nibbles: Vec<u8> = log
+            .latched("en", "rs", &DATA)
+            .into_iter()
+            .map(|(rs, nibble)| {
+                assert!(!rs);
+                nibble
+            })
+            .collect();
+
+        // Three 0x3 nibbles and a 0x2 nibble, then function set, display off,
+        // clear, entry mode, home and display on as whole bytes
+        assert_eq!(pulses, 16);
+        assert_eq!(
+            nibbles,
+            [0x3, 0x3, 0x3, 0x2, 0x2, 0x8, 0x0, 0x8, 0x0, 0x1, 0x0, 0x6, 0x8, 0x0, 0x0, 0xE]
+        );
+    }
+
+    #[test]
+    fn ws0010_init_lines_up_on_a_byte_boundary() {
+        let log = PinLog::new();
+        let lcd = HD44780::new_4bit_ws0010(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        let nibbles: Vec<u8> = log
+            .latched("en", "rs", &DATA)
+            .into_iter()
+            .map(|(_, nibble)| nibble)
+            .collect();
+
+        assert_eq!(
+            nibbles,
+            [
+                0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x2, 0x8, 0x0, 0x8, 0x1, 0x7, 0x0, 0x1, 0x0, 0x2,
+                0x0, 0x6, 0x0, 0xE
+            ]
+        );
+        assert_eq!(lcd.variant, ControllerVariant::Ws0010);
+    }
+
+    #[test]
+    fn raw_nibble_is_one_enable_pulse() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_4bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+        let before = log.latched("en", "rs", &DATA).len();
+
+        lcd.write_raw_nibble(0xF3, false, &mut Delay).unwrap();
+        lcd.write_raw_nibble(0x5, true, &mut Delay).unwrap();
+
+        let pulses = log.events().iter().filter(|&&e| e == ("en", true)).count();
+        let latched = log.latched("en", "rs", &DATA);
+        assert_eq!(latched.len(), before + 2);
+        assert_eq!(pulses, latched.len());
+        assert_eq!(latched[before..], [(false, 0x3), (true, 0x5)]);
+    }
+
+    fn pin_states(map: PinMap) -> Vec<(bool, u8)> {
+        let log = PinLog::new();
+        let mut bus = FourBitBus::from_pins_with_map(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            map,
+        );
+
+        bus.write(0b0011_0001, true, &mut Delay).unwrap();
+
+        log.latched("en", "rs", &DATA)
+    }
+
+    #[test]
+    fn straight_map_is_the_default() {
+        assert_eq!(
+            pin_states(PinMap::Straight),
+            [(true, 0b0011), (true, 0b0001)]
+        );
+    }
+
+    #[test]
+    fn reversed_map_swaps_the_pins() {
+        assert_eq!(
+            pin_states(PinMap::Reversed),
+            [(true, 0b1100), (true, 0b1000)]
+        );
+    }
+
+    #[test]
+    fn custom_map_moves_each_bit() {
+        // DB4 on d6, DB5 on d4, DB6 on d7 and DB7 on d5
+        assert_eq!(
+            pin_states(PinMap::Custom([2, 0, 3, 1])),
+            [(true, 0b0101), (true, 0b0100)]
+        );
+    }
+
+    #[test]
+    fn destroyed_pins_can_be_used_again() {
+        let log = PinLog::new();
+        let lcd = HD44780::new_4bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+        let init = log.latched("en", "rs", &DATA);
+
+        let (rs, en, d4, d5, d6, d7) = lcd.destroy().destroy();
+        let mut lcd = HD44780::new_4bit(rs, en, d4, d5, d6, d7, &mut Delay).unwrap();
+        lcd.write_str("again", &mut Delay).unwrap();
+
+        let latched = log.latched("en", "rs", &DATA);
+        assert_eq!(latched[init.len()..init.len() * 2], init[..]);
+        assert_eq!(latched.len(), init.len() * 2 + 10);
+    }
+
+    #[test]
+    fn destroy_gives_back_the_rw_pin() {
+        let log = PinLog::new();
+        let lcd = HD44780::new_4bit_rw(
+            log.pin("rs"),
+            log.pin("rw"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        let (_rs, mut rw, ..) = lcd.destroy().destroy();
+        rw.set_high().unwrap();
+
+        assert_eq!(log.events().last(), Some(&("rw", true)));
+    }
+
+    #[test]
+    fn read_without_rw_pin_is_unsupported() {
+        let log = PinLog::new();
+        let mut lcd = HD44780::new_4bit(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        assert_eq!(lcd.read_byte(&mut Delay), Err(Error::ReadUnsupported));
     }
 }
@@ -0,0 +1,177 @@
+use core::cell::RefCell;
+
+use crate::delay::DelayNs;
+use embedded_hal_0_2::blocking::i2c::Write;
+
+use crate::{
+    bus::DataBus,
+    error::{Error, Result},
+};
+
+/// The I2C address of the text controller on a Grove RGB LCD
+pub const TEXT_ADDRESS: u8 = 0x3E;
+
+/// The I2C address of the RGB backlight controller on a Grove RGB LCD
+pub const RGB_ADDRESS: u8 = 0x62;
+
+const COMMAND: u8 = 0x80;
+const DATA: u8 = 0x40;
+
+const MODE1: u8 = 0x00;
+const MODE2: u8 = 0x01;
+const BLUE: u8 = 0x02;
+const GREEN: u8 = 0x03;
+const RED: u8 = 0x04;
+const OUTPUT: u8 = 0x08;
+
+/// A bus to the JHD1313/AIP31068 text controller of a Seeed Grove RGB LCD.
+///
+/// The I2C peripheral is borrowed from a `RefCell` so that the
+/// [RgbBacklight](struct.RgbBacklight.html) on the same module can share it.
+pub struct GroveRgbBus<'a, I2C: Write> {
+    i2c_bus: &'a RefCell<I2C>,
+}
+
+impl<'a, I2C: Write> GroveRgbBus<'a, I2C> {
+    pub fn new(i2c_bus: &'a RefCell<I2C>) -> GroveRgbBus<'a, I2C> {
+        GroveRgbBus { i2c_bus }
+    }
+}
+
+impl<'a, I2C: Write> DataBus for GroveRgbBus<'a, I2C> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, _delay: &mut D) -> Result<()> {
+        let control = match data {
+            false => COMMAND,
+            true => DATA,
+        };
+
+        self.i2c_bus
+            .try_borrow_mut()
+            .map_err(|_| Error::Bus)?
+            .write(TEXT_ADDRESS, &[control, byte])
+            .map_err(|_| Error::Bus)
+    }
+}
+
+/// The PCA9633 style RGB backlight controller of a Seeed Grove RGB LCD
+///
+/// ```rust,ignore
+/// let i2c = RefCell::new(i2c);
+/// let (mut lcd, mut backlight) = HD44780::new_grove_rgb(&i2c, &mut delay)?;
+///
+/// backlight.set_rgb(255, 0, 0)?;
+/// lcd.write_str("Red alert", &mut delay)?;
+/// ```
+pub struct RgbBacklight<'a, I2C: Write> {
+    i2c_bus: &'a RefCell<I2C>,
+}
+
+impl<'a, I2C: Write> RgbBacklight<'a, I2C> {
+    /// Take the backlight controller out of sleep and drive all three LEDs
+    /// from their brightness registers, starting out white
+    pub fn new(i2c_bus: &'a RefCell<I2C>) -> Result<RgbBacklight<'a, I2C>> {
+        let mut backlight = RgbBacklight { i2c_bus };
+
+        backlight.set_register(MODE1, 0x00)?;
+        backlight.set_register(OUTPUT, 0xFF)?;
+        backlight.set_register(MODE2, 0x20)?;
+        backlight.set_rgb(255, 255, 255)?;
+
+        Ok(backlight)
+    }
+
+    /// Set the brightness of the red, green and blue backlight LEDs
+    pub fn set_rgb(&mut self, r: u8, g: u8, b: u8) -> Result<()> {
+        self.set_register(RED, r)?;
+        self.set_register(GREEN, g)?;
+        self.set_register(BLUE, b)?;
+
+        Ok(())
+    }
+
+    fn set_register(&mut self, register: u8, value: u8) -> Result<()> {
+        self.i2c_bus
+            .try_borrow_mut()
+            .map_err(|_| Error::Bus)?
+            .write(RGB_ADDRESS, &[register, value])
+            .map_err(|_| Error::Bus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::RefCell;
+    use std::vec;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{Delay, I2cLog};
+    use crate::{DisplayMode, HD44780};
+
+    fn writes_to(log: &I2cLog, address: u8) -> Vec<Vec<u8>> {
+        log.writes()
+            .into_iter()
+            .filter(|&(a, _)| a == address)
+            .map(|(_, bytes)| bytes)
+            .collect()
+    }
+
+    #[test]
+    fn text_init_and_control_bytes() {
+        let log = I2cLog::new();
+        let i2c = RefCell::new(log.i2c());
+        let (mut lcd, _backlight) = HD44780::new_grove_rgb(&i2c, &mut Delay).unwrap();
+
+        let commands: Vec<u8> = writes_to(&log, TEXT_ADDRESS)
+            .into_iter()
+            .map(|bytes| {
+                assert_eq!(bytes[0], COMMAND);
+                bytes[1]
+            })
+            .collect();
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(commands, [0x28, 0x28, 0x28, 0x28, 0x08, 0x01, 0x06, on]);
+
+        log.clear();
+        lcd.write_str("Hi", &mut Delay).unwrap();
+        assert_eq!(
+            log.writes(),
+            [
+                (TEXT_ADDRESS, vec![DATA, b'H']),
+                (TEXT_ADDRESS, vec![DATA, b'i']),
+            ]
+        );
+    }
+
+    #[test]
+    fn backlight_registers() {
+        let log = I2cLog::new();
+        let i2c = RefCell::new(log.i2c());
+        let (_lcd, mut backlight) = HD44780::new_grove_rgb(&i2c, &mut Delay).unwrap();
+
+        assert_eq!(
+            writes_to(&log, RGB_ADDRESS),
+            [
+                vec![MODE1, 0x00],
+                vec![OUTPUT, 0xFF],
+                vec![MODE2, 0x20],
+                vec![RED, 255],
+                vec![GREEN, 255],
+                vec![BLUE, 255],
+            ]
+        );
+
+        log.clear();
+        backlight.set_rgb(10, 20, 30).unwrap();
+        assert_eq!(
+            log.writes(),
+            [
+                (RGB_ADDRESS, vec![RED, 10]),
+                (RGB_ADDRESS, vec![GREEN, 20]),
+                (RGB_ADDRESS, vec![BLUE, 30]),
+            ]
+        );
+    }
+}
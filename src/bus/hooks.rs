@@ -0,0 +1,173 @@
+/// Functions called at fixed points while a bus clocks a transfer out to the
+/// `HD44780`, for debugging without a logic analyzer or for driving extra
+/// hardware (such as a latch) in step with the bus.
+///
+/// Every hook receives the value on the data lines (a whole byte on an 8-bit
+/// bus, a nibble in the lower 4 bits on a 4-bit bus) and whether the
+/// register select line is set for data. Hooks are plain `fn` pointers and
+/// are called synchronously from within the transfer, so they should return
+/// quickly and must not block on the display. An unset hook costs a single
+/// branch per call site.
+///
+/// ```rust,ignore
+/// fn strobe_latch(_value: u8, _data: bool) {
+///     // ...
+/// }
+///
+/// lcd.set_bus_hooks(BusHooks {
+///     after_en_fall: Some(strobe_latch),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct BusHooks {
+    /// Called before the value is put on the data lines
+    pub before_data_set: Option<fn(u8, bool)>,
+    /// Called once the enable pin has been driven high
+    pub after_en_rise: Option<fn(u8, bool)>,
+    /// Called once the enable pin has been driven low and the value latched
+    pub after_en_fall: Option<fn(u8, bool)>,
+}
+
+impl BusHooks {
+    pub(crate) fn before_data_set(&self, value: u8, data: bool) {
+        if let Some(hook) = self.before_data_set {
+            hook(value, data);
+        }
+    }
+
+    pub(crate) fn after_en_rise(&self, value: u8, data: bool) {
+        if let Some(hook) = self.after_en_rise {
+            hook(value, data);
+        }
+    }
+
+    pub(crate) fn after_en_fall(&self, value: u8, data: bool) {
+        if let Some(hook) = self.after_en_fall {
+            hook(value, data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::bus::{DataBus, EightBitBus, FourBitBus};
+    use crate::mock::{Delay, PinLog};
+
+    #[derive(Debug, PartialEq)]
+    enum Step {
+        DataSet(u8, bool),
+        EnRise(u8, bool),
+        EnFall(u8, bool),
+    }
+
+    std::thread_local! {
+        static STEPS: RefCell<Vec<Step>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(step: Step) {
+        STEPS.with(|steps| steps.borrow_mut().push(step));
+    }
+
+    fn take_steps() -> Vec<Step> {
+        STEPS.with(|steps| steps.borrow_mut().drain(..).collect())
+    }
+
+    fn hooks() -> BusHooks {
+        BusHooks {
+            before_data_set: Some(|value, data| record(Step::DataSet(value, data))),
+            after_en_rise: Some(|value, data| record(Step::EnRise(value, data))),
+            after_en_fall: Some(|value, data| record(Step::EnFall(value, data))),
+        }
+    }
+
+    #[test]
+    fn four_bit_hooks_see_each_nibble() {
+        let log = PinLog::new();
+        let mut bus = FourBitBus::from_pins(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+        bus.set_hooks(hooks());
+        take_steps();
+
+        bus.write(0xA5, true, &mut Delay).unwrap();
+
+        assert_eq!(
+            take_steps(),
+            [
+                Step::DataSet(0x0A, true),
+                Step::EnRise(0x0A, true),
+                Step::EnFall(0x0A, true),
+                Step::DataSet(0x05, true),
+                Step::EnRise(0x05, true),
+                Step::EnFall(0x05, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn eight_bit_hooks_see_the_whole_byte() {
+        let log = PinLog::new();
+        let mut bus = EightBitBus::from_pins(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+        bus.set_hooks(hooks());
+        take_steps();
+
+        bus.write(0x01, false, &mut Delay).unwrap();
+
+        assert_eq!(
+            take_steps(),
+            [
+                Step::DataSet(0x01, false),
+                Step::EnRise(0x01, false),
+                Step::EnFall(0x01, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn unset_hooks_are_skipped() {
+        let log = PinLog::new();
+        let mut bus = FourBitBus::from_pins(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+        bus.set_hooks(BusHooks {
+            after_en_fall: Some(|value, data| record(Step::EnFall(value, data))),
+            ..Default::default()
+        });
+        take_steps();
+
+        bus.write(0x42, false, &mut Delay).unwrap();
+
+        assert_eq!(
+            take_steps(),
+            [Step::EnFall(0x04, false), Step::EnFall(0x02, false)]
+        );
+    }
+}
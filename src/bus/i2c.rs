@@ -1,56 +1,477 @@
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
-use embedded_hal::blocking::i2c::Write;
+use crate::delay::DelayNs;
+use embedded_hal_0_2::blocking::i2c::{Read, Write};
 
-use crate::{bus::DataBus, error::Result};
+use crate::{
+    bus::{reset_by_nibbles, Backlight, DataBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
 
 pub struct I2CBus<I2C: Write> {
     i2c_bus: I2C,
     address: u8,
+    pinout: BackpackPinout,
+    backlight: bool,
 }
 
-const BACKLIGHT: u8 = 0b0000_1000;
-const ENABLE: u8 = 0b0000_0100;
-// const READ_WRITE: u8 = 0b0000_0010; // Not used as no reading of the `HD44780` is done
-const REGISTER_SELECT: u8 = 0b0000_0001;
+/// Which expander output each display line of an I2C backpack is wired to,
+/// as bit numbers 0 to 7
+///
+/// The default is the common PCF8574 layout: RS on P0, R/W on P1, EN on P2,
+/// the backlight on P3 and DB4 to DB7 on P4 to P7.
+///
+/// ```rust,ignore
+/// let pinout = BackpackPinout {
+///     rs: 2,
+///     en: 0,
+///     ..BackpackPinout::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackpackPinout {
+    pub rs: u8,
+    pub rw: u8,
+    pub en: u8,
+    pub backlight: u8,
+    /// The output DB4 is on, with DB5 to DB7 on the three above it
+    pub data_shift: u8,
+    /// The backlight is lit by driving its output low
+    pub backlight_active_low: bool,
+}
+
+impl Default for BackpackPinout {
+    fn default() -> BackpackPinout {
+        BackpackPinout {
+            rs: 0,
+            rw: 1,
+            en: 2,
+            backlight: 3,
+            data_shift: 4,
+            backlight_active_low: false,
+        }
+    }
+}
+
+impl BackpackPinout {
+    /// The expander outputs for a nibble (in the low four bits) with enable
+    /// low. R/W is always low as the bus only ever writes.
+    fn pack(&self, nibble: u8, data: bool, backlight: bool) -> u8 {
+        let mut byte = (nibble & 0x0F) << self.data_shift;
+
+        if data {
+            byte |= 1 << self.rs;
+        }
+        if backlight != self.backlight_active_low {
+            byte |= 1 << self.backlight;
+        }
+
+        byte
+    }
+
+    fn enable(&self) -> u8 {
+        1 << self.en
+    }
+}
 
 impl<I2C: Write> I2CBus<I2C> {
     pub fn new(i2c_bus: I2C, address: u8) -> I2CBus<I2C> {
-        I2CBus { i2c_bus, address }
+        I2CBus::with_pinout(i2c_bus, address, BackpackPinout::default())
+    }
+
+    /// Create the bus for a backpack that isn't wired the common way, see
+    /// [BackpackPinout](struct.BackpackPinout.html)
+    pub fn with_pinout(i2c_bus: I2C, address: u8, pinout: BackpackPinout) -> I2CBus<I2C> {
+        I2CBus {
+            i2c_bus,
+            address,
+            pinout,
+            backlight: true,
+        }
     }
 
     /// Write a nibble to the lcd
-    /// The nibble should be in the upper part of the byte
-    fn write_nibble<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        nibble: u8,
-        data: bool,
-        delay: &mut D,
-    ) {
-        let rs = match data {
-            false => 0u8,
-            true => REGISTER_SELECT,
-        };
-        let byte = nibble | rs | BACKLIGHT;
+    /// The nibble should be in the lower part of the byte
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        let byte = self.pinout.pack(nibble, data, self.backlight);
+
+        // Set up the nibble with enable low, then raise enable while holding
+        // the nibble on the data lines
+        self.i2c_bus
+            .write(self.address, &[byte, byte | self.pinout.enable()])
+            .map_err(|_| Error::Bus)?;
+        delay.delay_ms(2);
+        // Lower enable for the `HD44780` to latch the nibble
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .map_err(|_| Error::Bus)?;
 
-        let _ = self.i2c_bus.write(self.address, &[byte, byte | ENABLE]);
-        delay.delay_ms(2u8);
-        let _ = self.i2c_bus.write(self.address, &[byte]);
+        Ok(())
     }
 }
 
 impl<I2C: Write> DataBus for I2CBus<I2C> {
-    fn write<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        byte: u8,
-        data: bool,
-        delay: &mut D,
-    ) -> Result<()> {
-        let upper_nibble = byte & 0xF0;
-        self.write_nibble(upper_nibble, data, delay);
-
-        let lower_nibble = (byte & 0x0F) << 4;
-        self.write_nibble(lower_nibble, data, delay);
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        let upper_nibble = byte >> 4;
+        self.write_nibble(upper_nibble, data, delay)?;
+
+        let lower_nibble = byte & 0x0F;
+        self.write_nibble(lower_nibble, data, delay)?;
 
         Ok(())
     }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            self.write_nibble(nibble, false, delay)
+        })
+    }
+}
+
+impl<I2C: Write> Backlight for I2CBus<I2C> {
+    fn set_backlight(&mut self, on: bool) -> Result<()> {
+        self.backlight = on;
+
+        // Transfers are whole bytes, so this always lands with enable low and
+        // never between the two nibbles of a byte
+        let byte = self.pinout.pack(0, false, on);
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .map_err(|_| Error::Bus)
+    }
+}
+
+/// The addresses a PCF8574 (0x20 to 0x27) or PCF8574A (0x38 to 0x3F)
+/// backpack can be strapped to, in the order they are probed
+const BACKPACK_ADDRESSES: [u8; 16] = [
+    0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E, 0x3F,
+];
+
+/// Patterns written to a candidate expander and read back, all with enable
+/// low so the display ignores them. The data lines are only driven by the
+/// display while R/W is high, so an expander reads back what it was given.
+const PROBE_PATTERNS: [u8; 2] = [0b0000_0000, 0b1111_0000];
+
+/// Find the address of a PCF8574 / PCF8574A I2C backpack on the bus.
+///
+/// Writes to and reads from each of 0x20 to 0x27 then 0x38 to 0x3F in turn,
+/// and returns the first address whose outputs read back the patterns
+/// written to them. Other devices that acknowledge their address, such as
+/// an MCP23008 at 0x20, read back their registers instead and are skipped
+/// after a single one byte write.
+///
+/// A matching backpack is left with all of its outputs low. Addresses that
+/// don't answer are only ever addressed, never written to.
+///
+/// ```rust,ignore
+/// let address = scan_backpack(&mut i2c)?.expect("no backpack found");
+/// ```
+pub fn scan_backpack<I2C: Write + Read>(i2c: &mut I2C) -> Result<Option<u8>> {
+    for &address in BACKPACK_ADDRESSES.iter() {
+        if is_backpack(i2c, address) {
+            i2c.write(address, &[0]).map_err(|_| Error::Bus)?;
+
+            return Ok(Some(address));
+        }
+    }
+
+    Ok(None)
+}
+
+fn is_backpack<I2C: Write + Read>(i2c: &mut I2C, address: u8) -> bool {
+    PROBE_PATTERNS.iter().all(|&pattern| {
+        let mut read = [0];
+
+        i2c.write(address, &[pattern]).is_ok()
+            && i2c.read(address, &mut read).is_ok()
+            && read[0] == pattern
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::mock::{Delay, I2cLog};
+    use crate::HD44780;
+
+    const ADDRESS: u8 = 0x27;
+
+    #[test]
+    fn command_byte_sequence() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_i2c(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.write_command(0x01, &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![0b0000_1000, 0b0000_1100]),
+                (ADDRESS, vec![0b0000_1000]),
+                (ADDRESS, vec![0b0001_1000, 0b0001_1100]),
+                (ADDRESS, vec![0b0001_1000]),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_sets_register_select() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_i2c(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.write_byte(b'A', &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![0b0100_1001, 0b0100_1101]),
+                (ADDRESS, vec![0b0100_1001]),
+                (ADDRESS, vec![0b0001_1001, 0b0001_1101]),
+                (ADDRESS, vec![0b0001_1001]),
+            ]
+        );
+    }
+
+    /// The `(rs, byte)` transfers the display latches from the expander
+    /// outputs, pairing up nibbles on each falling edge of enable
+    fn latched(log: &I2cLog) -> Vec<(bool, u8)> {
+        let mut enable = false;
+        let mut nibbles = Vec::new();
+
+        for (_, bytes) in log.writes() {
+            for byte in bytes {
+                let high = byte & 0b0000_0100 != 0;
+                if enable && !high {
+                    nibbles.push((byte & 0b0000_0001 != 0, byte >> 4));
+                }
+                enable = high;
+            }
+        }
+
+        nibbles
+            .chunks(2)
+            .map(|pair| (pair[0].0, (pair[0].1 << 4) | pair[1].1))
+            .collect()
+    }
+
+    #[test]
+    fn backlight_bit_follows_set_backlight() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_i2c(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.set_backlight(false).unwrap();
+        assert_eq!(log.writes(), [(ADDRESS, vec![0b0000_0000])]);
+
+        log.clear();
+        lcd.write_byte(b'A', &mut Delay).unwrap();
+        assert!(log
+            .writes()
+            .iter()
+            .all(|(_, bytes)| bytes.iter().all(|byte| byte & 0b0000_1000 == 0)));
+
+        log.clear();
+        lcd.set_backlight(true).unwrap();
+        assert_eq!(log.writes(), [(ADDRESS, vec![0b0000_1000])]);
+    }
+
+    #[test]
+    fn toggling_backlight_while_writing_keeps_text_intact() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_i2c(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        for (i, c) in "Hello".chars().enumerate() {
+            lcd.set_backlight(i % 2 == 0).unwrap();
+            lcd.write_char(c, &mut Delay).unwrap();
+        }
+
+        let text: Vec<u8> = latched(&log)
+            .into_iter()
+            .map(|(rs, byte)| {
+                assert!(rs);
+                byte
+            })
+            .collect();
+        assert_eq!(text, b"Hello");
+    }
+
+    /// The devices on a simulated bus, recording the addresses written to
+    #[derive(Default)]
+    struct Devices {
+        /// A PCF8574 that reads back its outputs
+        expander: Option<u8>,
+        /// A register based device that reads back 0xFF
+        other: Option<u8>,
+        outputs: u8,
+        written: Vec<(u8, u8)>,
+    }
+
+    impl Write for Devices {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> core::result::Result<(), ()> {
+            if Some(address) == self.expander {
+                self.outputs = bytes[bytes.len() - 1];
+            } else if Some(address) != self.other {
+                return Err(());
+            }
+
+            self.written.push((address, bytes[0]));
+            Ok(())
+        }
+    }
+
+    impl Read for Devices {
+        type Error = ();
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> core::result::Result<(), ()> {
+            match address {
+                a if Some(a) == self.expander => buffer[0] = self.outputs,
+                a if Some(a) == self.other => buffer[0] = 0xFF,
+                _ => return Err(()),
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scan_finds_a_pcf8574a_backpack() {
+        let mut bus = Devices {
+            expander: Some(0x3F),
+            ..Devices::default()
+        };
+
+        assert_eq!(scan_backpack(&mut bus), Ok(Some(0x3F)));
+        assert_eq!(bus.written, [(0x3F, 0x00), (0x3F, 0xF0), (0x3F, 0x00)]);
+    }
+
+    #[test]
+    fn scan_skips_other_devices_that_ack() {
+        let mut bus = Devices {
+            expander: Some(0x27),
+            other: Some(0x20),
+            ..Devices::default()
+        };
+
+        assert_eq!(scan_backpack(&mut bus), Ok(Some(0x27)));
+        assert_eq!(bus.written[0], (0x20, 0x00));
+        assert!(bus.written[1..].iter().all(|&(address, _)| address == 0x27));
+    }
+
+    #[test]
+    fn scan_finds_nothing_on_an_empty_bus() {
+        let mut bus = Devices {
+            other: Some(0x3C),
+            ..Devices::default()
+        };
+
+        assert_eq!(scan_backpack(&mut bus), Ok(None));
+        assert_eq!(bus.written, [(0x3C, 0x00)]);
+        assert_eq!(
+            HD44780::new_i2c_autodetect(bus, &mut Delay).err(),
+            Some(Error::NoDevice)
+        );
+    }
+
+    #[test]
+    fn pinout_packs_nibbles() {
+        let swapped = BackpackPinout {
+            rs: 2,
+            en: 0,
+            ..BackpackPinout::default()
+        };
+        let active_low = BackpackPinout {
+            backlight_active_low: true,
+            ..BackpackPinout::default()
+        };
+        let low_data = BackpackPinout {
+            rs: 4,
+            rw: 5,
+            en: 6,
+            backlight: 7,
+            data_shift: 0,
+            backlight_active_low: false,
+        };
+
+        // (pinout, nibble, data, backlight, packed, enable)
+        let table = [
+            (
+                BackpackPinout::default(),
+                0x4,
+                true,
+                true,
+                0b0100_1001,
+                0b0000_0100,
+            ),
+            (
+                BackpackPinout::default(),
+                0x1,
+                false,
+                false,
+                0b0001_0000,
+                0b0000_0100,
+            ),
+            (swapped, 0x4, true, true, 0b0100_1100, 0b0000_0001),
+            (swapped, 0xF, false, true, 0b1111_1000, 0b0000_0001),
+            (active_low, 0x4, true, true, 0b0100_0001, 0b0000_0100),
+            (active_low, 0x4, true, false, 0b0100_1001, 0b0000_0100),
+            (low_data, 0xA, true, true, 0b1001_1010, 0b0100_0000),
+        ];
+
+        for &(pinout, nibble, data, backlight, packed, enable) in table.iter() {
+            assert_eq!(pinout.pack(nibble, data, backlight), packed, "{:?}", pinout);
+            assert_eq!(pinout.enable(), enable, "{:?}", pinout);
+        }
+    }
+
+    #[test]
+    fn swapped_rs_and_en_byte_sequence() {
+        let log = I2cLog::new();
+        let pinout = BackpackPinout {
+            rs: 2,
+            en: 0,
+            ..BackpackPinout::default()
+        };
+        let mut lcd = HD44780::new_i2c_with_pinout(log.i2c(), ADDRESS, pinout, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.write_byte(b'A', &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![0b0100_1100, 0b0100_1101]),
+                (ADDRESS, vec![0b0100_1100]),
+                (ADDRESS, vec![0b0001_1100, 0b0001_1101]),
+                (ADDRESS, vec![0b0001_1100]),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_low_backlight_is_off_when_driven_high() {
+        let log = I2cLog::new();
+        let pinout = BackpackPinout {
+            backlight_active_low: true,
+            ..BackpackPinout::default()
+        };
+        let mut lcd = HD44780::new_i2c_with_pinout(log.i2c(), ADDRESS, pinout, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.set_backlight(false).unwrap();
+        lcd.set_backlight(true).unwrap();
+        assert_eq!(
+            log.writes(),
+            [(ADDRESS, vec![0b0000_1000]), (ADDRESS, vec![0b0000_0000])]
+        );
+    }
 }
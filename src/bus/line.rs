@@ -0,0 +1,199 @@
+use crate::delay::DelayNs;
+use crate::timing::TimingConfig;
+
+/// Time in microseconds added to the settle time before latching the data
+/// lines, covering the HD44780's own data setup time of 195 ns
+const SETUP_MARGIN_US: u16 = 1;
+
+/// How slowly the lines between the bus and the `HD44780` settle, for
+/// wiring such as open-drain level shifters with weak pull-ups where an edge
+/// takes several microseconds.
+///
+/// The GPIO buses wait for the slower of the two edges plus a 1 µs margin
+/// after setting the data lines before raising the enable pin, hold the
+/// enable pin high for an extra rise time, and wait for a fall time after
+/// lowering it before the data lines change again. The default of zero adds
+/// no waits. `byte_time` on the driver shows what the waits cost.
+///
+/// The I2C backpack buses don't use this: each expander write already takes
+/// far longer than any edge.
+///
+/// ```rust,ignore
+/// lcd.set_line_characteristics(LineCharacteristics {
+///     rise_time_us: 5,
+///     fall_time_us: 1,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCharacteristics {
+    /// Time in microseconds for a line to settle after being driven high
+    pub rise_time_us: u16,
+    /// Time in microseconds for a line to settle after being driven low
+    pub fall_time_us: u16,
+}
+
+impl LineCharacteristics {
+    /// Time in microseconds for freshly set data lines to settle, with the
+    /// margin, or 0 for lines that settle at once
+    fn setup_us(&self) -> u16 {
+        match self.rise_time_us.max(self.fall_time_us) {
+            0 => 0,
+            settle => settle.saturating_add(SETUP_MARGIN_US),
+        }
+    }
+
+    /// Wait for freshly set data lines to settle before they are latched
+    pub(crate) fn data_setup<D: DelayNs>(&self, delay: &mut D) {
+        let setup = self.setup_us();
+
+        if setup > 0 {
+            delay.delay_us(u32::from(setup));
+        }
+    }
+
+    /// Time in microseconds one strobe of the enable pin takes with
+    /// `timing`, from setting the data lines to when they can change again
+    pub(crate) fn strobe_us(&self, timing: &TimingConfig) -> u32 {
+        u32::from(self.setup_us())
+            + timing.enable_pulse_len_us()
+            + u32::from(self.rise_time_us)
+            + u32::from(self.fall_time_us)
+    }
+
+    /// Keep the enable pin high long enough to cover its own rise time
+    pub(crate) fn enable_pulse<D: DelayNs>(&self, delay: &mut D) {
+        if self.rise_time_us > 0 {
+            delay.delay_us(u32::from(self.rise_time_us));
+        }
+    }
+
+    /// Wait for the enable pin to actually fall before the data lines change
+    pub(crate) fn enable_hold<D: DelayNs>(&self, delay: &mut D) {
+        if self.fall_time_us > 0 {
+            delay.delay_us(u32::from(self.fall_time_us));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::time::Duration;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::bus::{DataBus, EightBitBus, FourBitBus};
+    use crate::mock::{Event, EventLog, Pin, PinLog};
+
+    const SLOW: LineCharacteristics = LineCharacteristics {
+        rise_time_us: 5,
+        fall_time_us: 3,
+    };
+
+    fn delays(log: &EventLog) -> Vec<u32> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::DelayUs(us) => Some(us),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn eight_bit_bus(
+        pins: &PinLog,
+    ) -> EightBitBus<Pin, Pin, Pin, Pin, Pin, Pin, Pin, Pin, Pin, Pin> {
+        EightBitBus::from_pins(
+            pins.pin("rs"),
+            pins.pin("en"),
+            pins.pin("d0"),
+            pins.pin("d1"),
+            pins.pin("d2"),
+            pins.pin("d3"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+        )
+    }
+
+    #[test]
+    fn eight_bit_timing_unchanged_by_default() {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = eight_bit_bus(&pins);
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [2000]);
+    }
+
+    #[test]
+    fn eight_bit_timing_covers_slow_edges() {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = eight_bit_bus(&pins);
+        bus.set_line_characteristics(SLOW);
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [6, 2000, 5, 3]);
+    }
+
+    #[test]
+    fn four_bit_timing_covers_slow_edges_for_each_nibble() {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = FourBitBus::from_pins(
+            pins.pin("rs"),
+            pins.pin("en"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+        );
+        bus.set_line_characteristics(SLOW);
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [6, 2000, 5, 3, 6, 2000, 5, 3]);
+    }
+
+    #[test]
+    fn byte_time_adds_up_the_waits() {
+        let pins = PinLog::new();
+        let mut lcd = crate::HD44780::new_4bit(
+            pins.pin("rs"),
+            pins.pin("en"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+            &mut crate::mock::Delay,
+        )
+        .unwrap();
+        lcd.set_timing(crate::TimingConfig::fast());
+        assert_eq!(lcd.byte_time(), Duration::from_micros(2 + 40));
+
+        lcd.set_line_characteristics(SLOW);
+        assert_eq!(lcd.byte_time(), Duration::from_micros(2 * 15 + 40));
+
+        let mut lcd = crate::HD44780::new_8bit(
+            pins.pin("rs"),
+            pins.pin("en"),
+            pins.pin("d0"),
+            pins.pin("d1"),
+            pins.pin("d2"),
+            pins.pin("d3"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+            &mut crate::mock::Delay,
+        )
+        .unwrap();
+        lcd.set_line_characteristics(SLOW);
+        assert_eq!(lcd.byte_time(), Duration::from_micros(6 + 2000 + 8 + 100));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::delay::DelayNs;
+use embedded_hal_0_2::blocking::i2c::Write;
+
+use crate::{
+    bus::{reset_by_nibbles, DataBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// A 4-bit bus through an MCP23008 I2C port expander, wired the way the
+/// Adafruit I2C/SPI character LCD backpack is:
+///
+/// | GP0 | GP1 | GP2 | GP3..GP6 | GP7       |
+/// |-----|-----|-----|----------|-----------|
+/// | -   | RS  | EN  | D4..D7   | Backlight |
+pub struct Mcp23008Bus<I2C: Write> {
+    i2c_bus: I2C,
+    address: u8,
+}
+
+const IODIR: u8 = 0x00;
+const GPIO: u8 = 0x09;
+
+const REGISTER_SELECT: u8 = 0b0000_0010;
+const ENABLE: u8 = 0b0000_0100;
+const DATA_SHIFT: u8 = 3;
+const BACKLIGHT: u8 = 0b1000_0000;
+
+impl<I2C: Write> Mcp23008Bus<I2C> {
+    /// Configure every pin of the expander at `address` as an output and
+    /// create a bus over it
+    pub fn from_i2c(mut i2c_bus: I2C, address: u8) -> Result<Mcp23008Bus<I2C>> {
+        i2c_bus
+            .write(address, &[IODIR, 0x00])
+            .map_err(|_| Error::Bus)?;
+
+        Ok(Mcp23008Bus { i2c_bus, address })
+    }
+
+    fn set_gpio(&mut self, value: u8) -> Result<()> {
+        self.i2c_bus
+            .write(self.address, &[GPIO, value])
+            .map_err(|_| Error::Bus)
+    }
+
+    /// Write the lower 4 bits of `nibble` to the lcd
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        let rs = match data {
+            false => 0u8,
+            true => REGISTER_SELECT,
+        };
+        let value = ((nibble & 0x0F) << DATA_SHIFT) | rs | BACKLIGHT;
+
+        self.set_gpio(value)?;
+        self.set_gpio(value | ENABLE)?;
+        delay.delay_ms(2);
+        self.set_gpio(value)?;
+
+        Ok(())
+    }
+}
+
+impl<I2C: Write> DataBus for Mcp23008Bus<I2C> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_nibble(byte >> 4, data, delay)?;
+        self.write_nibble(byte & 0x0F, data, delay)?;
+
+        Ok(())
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            self.write_nibble(nibble, false, delay)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_hal_0_2::blocking::i2c::Write;
+
+    use super::*;
+    use crate::mock::{Delay, I2cLog};
+    use crate::HD44780;
+
+    const ADDRESS: u8 = 0x20;
+
+    struct Unplugged;
+
+    impl Write for Unplugged {
+        type Error = ();
+
+        fn write(&mut self, _address: u8, _bytes: &[u8]) -> core::result::Result<(), ()> {
+            Err(())
+        }
+    }
+
+    #[test]
+    fn configures_all_pins_as_outputs() {
+        let log = I2cLog::new();
+        Mcp23008Bus::from_i2c(log.i2c(), ADDRESS).unwrap();
+
+        assert_eq!(log.writes(), [(ADDRESS, vec![IODIR, 0x00])]);
+    }
+
+    #[test]
+    fn setup_failure_is_an_error() {
+        assert!(Mcp23008Bus::from_i2c(Unplugged, ADDRESS).is_err());
+        assert!(HD44780::new_mcp23008(Unplugged, ADDRESS, &mut Delay).is_err());
+    }
+
+    #[test]
+    fn data_byte_sequence() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_mcp23008(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.write_byte(0xA5, &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![GPIO, 0b1101_0010]),
+                (ADDRESS, vec![GPIO, 0b1101_0110]),
+                (ADDRESS, vec![GPIO, 0b1101_0010]),
+                (ADDRESS, vec![GPIO, 0b1010_1010]),
+                (ADDRESS, vec![GPIO, 0b1010_1110]),
+                (ADDRESS, vec![GPIO, 0b1010_1010]),
+            ]
+        );
+    }
+}
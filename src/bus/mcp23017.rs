@@ -0,0 +1,178 @@
+use crate::delay::DelayNs;
+use embedded_hal_0_2::blocking::i2c::Write;
+
+use crate::{
+    bus::{reset_by_nibbles, DataBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// One of the two 8-bit ports of an MCP23017
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Port {
+    A,
+    B,
+}
+
+impl Port {
+    fn iodir(self) -> u8 {
+        match self {
+            Port::A => 0x00,
+            Port::B => 0x01,
+        }
+    }
+
+    fn olat(self) -> u8 {
+        match self {
+            Port::A => 0x14,
+            Port::B => 0x15,
+        }
+    }
+}
+
+/// A 4-bit bus through one port of an MCP23017 I2C port expander, so that a
+/// single expander can drive two displays. Each port is wired the same way
+/// as a PCF8574 backpack:
+///
+/// | Px0 | Px1 | Px2 | Px3       | Px4..Px7 |
+/// |-----|-----|-----|-----------|----------|
+/// | RS  | -   | EN  | Backlight | D4..D7   |
+///
+/// The bus only ever writes its own port's direction and output latch
+/// registers, so the other port is left alone. To drive both ports, give
+/// each bus its own handle to the I2C peripheral, for example from the
+/// `shared-bus` crate:
+///
+/// ```rust,ignore
+/// let i2c = shared_bus::BusManagerSimple::new(i2c);
+///
+/// let mut left = HD44780::new_mcp23017(i2c.acquire_i2c(), 0x20, Port::A, &mut delay)?;
+/// let mut right = HD44780::new_mcp23017(i2c.acquire_i2c(), 0x20, Port::B, &mut delay)?;
+/// ```
+pub struct Mcp23017Bus<I2C: Write> {
+    i2c_bus: I2C,
+    address: u8,
+    port: Port,
+}
+
+const REGISTER_SELECT: u8 = 0b0000_0001;
+const ENABLE: u8 = 0b0000_0100;
+const BACKLIGHT: u8 = 0b0000_1000;
+
+impl<I2C: Write> Mcp23017Bus<I2C> {
+    /// Configure every pin of `port` on the expander at `address` as an
+    /// output and create a bus over it
+    pub fn from_i2c(mut i2c_bus: I2C, address: u8, port: Port) -> Result<Mcp23017Bus<I2C>> {
+        i2c_bus
+            .write(address, &[port.iodir(), 0x00])
+            .map_err(|_| Error::Bus)?;
+
+        Ok(Mcp23017Bus {
+            i2c_bus,
+            address,
+            port,
+        })
+    }
+
+    fn set_latch(&mut self, value: u8) -> Result<()> {
+        self.i2c_bus
+            .write(self.address, &[self.port.olat(), value])
+            .map_err(|_| Error::Bus)
+    }
+
+    /// Write a nibble to the lcd
+    /// The nibble should be in the upper part of the byte
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        let rs = match data {
+            false => 0u8,
+            true => REGISTER_SELECT,
+        };
+        let value = nibble | rs | BACKLIGHT;
+
+        self.set_latch(value)?;
+        self.set_latch(value | ENABLE)?;
+        delay.delay_ms(2);
+        self.set_latch(value)?;
+
+        Ok(())
+    }
+}
+
+impl<I2C: Write> DataBus for Mcp23017Bus<I2C> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        let upper_nibble = byte & 0xF0;
+        self.write_nibble(upper_nibble, data, delay)?;
+
+        let lower_nibble = (byte & 0x0F) << 4;
+        self.write_nibble(lower_nibble, data, delay)?;
+
+        Ok(())
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            self.write_nibble(nibble << 4, false, delay)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{Delay, I2cLog};
+    use crate::HD44780;
+
+    const ADDRESS: u8 = 0x20;
+
+    #[test]
+    fn configures_only_its_own_port() {
+        let log = I2cLog::new();
+        Mcp23017Bus::from_i2c(log.i2c(), ADDRESS, Port::B).unwrap();
+
+        assert_eq!(log.writes(), [(ADDRESS, vec![0x01, 0x00])]);
+    }
+
+    #[test]
+    fn port_b_never_writes_port_a_latch() {
+        let log = I2cLog::new();
+        let mut left = HD44780::new_mcp23017(log.i2c(), ADDRESS, Port::A, &mut Delay).unwrap();
+        let mut right = HD44780::new_mcp23017(log.i2c(), ADDRESS, Port::B, &mut Delay).unwrap();
+        log.clear();
+
+        right.write_str("B", &mut Delay).unwrap();
+
+        let writes = log.writes();
+        assert!(!writes.is_empty());
+        assert!(writes.iter().all(|(_, bytes)| bytes[0] == 0x15));
+
+        log.clear();
+        left.write_str("A", &mut Delay).unwrap();
+
+        assert!(log.writes().iter().all(|(_, bytes)| bytes[0] == 0x14));
+    }
+
+    #[test]
+    fn data_byte_sequence() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_mcp23017(log.i2c(), ADDRESS, Port::A, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.write_byte(b'A', &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![0x14, 0b0100_1001]),
+                (ADDRESS, vec![0x14, 0b0100_1101]),
+                (ADDRESS, vec![0x14, 0b0100_1001]),
+                (ADDRESS, vec![0x14, 0b0001_1001]),
+                (ADDRESS, vec![0x14, 0b0001_1101]),
+                (ADDRESS, vec![0x14, 0b0001_1001]),
+            ]
+        );
+    }
+}
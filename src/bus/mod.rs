@@ -1,23 +1,130 @@
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use crate::delay::DelayNs;
 
+mod backlight;
+mod dimmer;
+mod dual_enable;
 mod eightbit;
 mod fourbit;
+mod grove_rgb;
+mod hooks;
 mod i2c;
+mod line;
+mod mcp23008;
+mod mcp23017;
+mod pin_map;
+mod port;
+mod serial164;
+mod shared;
+mod shift_register;
+mod st7032;
+mod st7036;
 
+pub use self::backlight::{Backlight, BacklightBus};
+pub use self::dimmer::{Brightness, DimmerBus};
+pub use self::dual_enable::{ActiveController, DualEnableBus};
 pub use self::eightbit::EightBitBus;
 pub use self::fourbit::FourBitBus;
-pub use self::i2c::I2CBus;
+pub use self::grove_rgb::{GroveRgbBus, RgbBacklight};
+pub use self::hooks::BusHooks;
+pub use self::i2c::{scan_backpack, BackpackPinout, I2CBus};
+pub use self::line::LineCharacteristics;
+pub use self::mcp23008::Mcp23008Bus;
+pub use self::mcp23017::{Mcp23017Bus, Port};
+pub use self::pin_map::PinMap;
+pub use self::port::{OutputPort, PortBus};
+pub use self::serial164::SerialBus164;
+pub use self::shared::{SharedBusManager, SharedFourBitBus};
+pub use self::shift_register::{ShiftRegisterBus, ShiftRegisterPinout};
+pub use self::st7032::St7032Bus;
+pub use self::st7036::St7036Bus;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::timing::TimingConfig;
 
 pub trait DataBus {
-    fn write<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        byte: u8,
-        data: bool,
-        delay: &mut D,
-    ) -> Result<()>;
-
-    // TODO
-    // fn read(...)
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()>;
+
+    /// Read a byte back from the display, from the data register when
+    /// `data` is true or the busy flag and address counter when it is false.
+    ///
+    /// Buses that can't read from the display return
+    /// `Error::ReadUnsupported`.
+    fn read<D: DelayNs>(&mut self, _data: bool, _delay: &mut D) -> Result<u8> {
+        Err(Error::ReadUnsupported)
+    }
+
+    /// Use the enable pulse and other bus level timing from `timing`.
+    ///
+    /// Buses with timing of their own, such as the I2C backpacks, ignore it.
+    fn set_timing(&mut self, _timing: &TimingConfig) {}
+
+    /// Bring the display into 4-bit mode, whatever mode it was left in.
+    ///
+    /// Buses that can send single nibbles follow the datasheet's
+    /// initialization by instruction, see
+    /// [reset_by_nibbles](fn.reset_by_nibbles.html). Buses that can only send
+    /// whole bytes send 0x33 then 0x32, which gets there from 8-bit mode.
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        self.write(0x33, false, delay)?;
+        delay.delay_ms(u32::from(timing.init.function_set_ms));
+
+        self.write(0x32, false, delay)?;
+        delay.delay_us(u32::from(timing.command_us));
+
+        Ok(())
+    }
+
+    /// Get ready for the cursor to be moved to `row`.
+    ///
+    /// Buses that drive the rows from more than one controller select the
+    /// one for `row`.
+    fn select_row(&mut self, _row: u8) {}
+
+    /// The controller the following transfers go to, on a bus that drives
+    /// the rows from more than one controller. Other buses return `None`.
+    fn selected_controller(&self) -> Option<ActiveController> {
+        None
+    }
+
+    /// Send the following transfers to `controller`, on a bus that drives
+    /// the rows from more than one controller. Other buses ignore it.
+    fn select_controller(&mut self, _controller: ActiveController) {}
 }
+
+/// The initialization by instruction from the HD44780 datasheet: three 0x3
+/// nibbles to land in 8-bit mode from any mode and nibble phase, then a 0x2
+/// nibble to switch to 4-bit mode.
+pub(crate) fn reset_by_nibbles<D, F>(
+    timing: &TimingConfig,
+    delay: &mut D,
+    mut write_nibble: F,
+) -> Result<()>
+where
+    D: DelayNs,
+    F: FnMut(u8, &mut D) -> Result<()>,
+{
+    write_nibble(0x3, delay)?;
+    delay.delay_ms(u32::from(timing.init.function_set_ms));
+
+    write_nibble(0x3, delay)?;
+    delay.delay_us(u32::from(timing.command_us));
+
+    write_nibble(0x3, delay)?;
+    delay.delay_us(u32::from(timing.command_us));
+
+    write_nibble(0x2, delay)?;
+    delay.delay_us(u32::from(timing.command_us));
+
+    Ok(())
+}
+
+/// A bus that can send a single 4-bit transfer, such as the four bit
+/// parallel bus
+pub trait NibbleBus {
+    /// Send the lower four bits of `nibble` with one pulse of the enable pin
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()>;
+}
+
+/// Stands in for a pin that isn't connected, such as the R/W pin of a
+/// display with R/W tied to ground
+pub struct NoPin;
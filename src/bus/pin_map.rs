@@ -0,0 +1,80 @@
+/// How the data bits of a 4-bit bus are wired to its four data pins, for
+/// boards where DB4 to DB7 of the display weren't routed to the pins in
+/// order.
+///
+/// Pins are numbered by where they are passed in, so pin 0 is the one given
+/// as `d4` and pin 3 the one given as `d7`.
+///
+/// ```rust,ignore
+/// // DB4 is on the d7 pin, DB5 on d6, DB6 on d5 and DB7 on d4
+/// let bus = FourBitBus::from_pins_with_map(rs, en, d4, d5, d6, d7, PinMap::Reversed);
+///
+/// // DB4 is on the d5 pin, DB5 on d4, DB6 on d7 and DB7 on d6
+/// let bus = FourBitBus::from_pins_with_map(rs, en, d4, d5, d6, d7, PinMap::Custom([1, 0, 3, 2]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PinMap {
+    /// DB4 to DB7 on pins 0 to 3
+    #[default]
+    Straight,
+    /// DB4 to DB7 on pins 3 to 0
+    Reversed,
+    /// DB4 to DB7 on the pins at each index, which should name every pin
+    /// from 0 to 3 once
+    Custom([u8; 4]),
+}
+
+impl PinMap {
+    fn order(&self) -> [u8; 4] {
+        match self {
+            PinMap::Straight => [0, 1, 2, 3],
+            PinMap::Reversed => [3, 2, 1, 0],
+            PinMap::Custom(pins) => *pins,
+        }
+    }
+
+    /// Move each bit of `nibble` onto the pin it is wired to
+    pub(crate) fn pins_for(&self, nibble: u8) -> u8 {
+        self.order()
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| nibble & (1 << bit) != 0)
+            .fold(0, |pins, (_, &pin)| pins | (1 << (pin & 0b11)))
+    }
+
+    /// Collect the levels read from the pins back into a nibble
+    pub(crate) fn nibble_for(&self, pins: u8) -> u8 {
+        self.order()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &pin)| pins & (1 << (pin & 0b11)) != 0)
+            .fold(0, |nibble, (bit, _)| nibble | (1 << bit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_leaves_bits_alone() {
+        for nibble in 0..16 {
+            assert_eq!(PinMap::Straight.pins_for(nibble), nibble);
+        }
+    }
+
+    #[test]
+    fn mapping_round_trips() {
+        let maps = [
+            PinMap::Straight,
+            PinMap::Reversed,
+            PinMap::Custom([2, 0, 3, 1]),
+        ];
+
+        for map in maps.iter() {
+            for nibble in 0..16 {
+                assert_eq!(map.nibble_for(map.pins_for(nibble)), nibble);
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use crate::delay::DelayNs;
+use crate::digital::OutputPin;
+
+use crate::{
+    bus::{DataBus, LineCharacteristics},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// The port's own enable pulse in microseconds, used when the timing leaves
+/// it up to the bus. It only has to be 450ns wide.
+const ENABLE_PULSE_US: u16 = 1;
+
+/// Eight outputs that can be set together, such as a whole GPIO port, with
+/// bit 0 of the value driving db0 of the display
+///
+/// Eight separate `OutputPin`s also make an `OutputPort`, either as a tuple
+/// `(d0, d1, d2, d3, d4, d5, d6, d7)` or as an array.
+pub trait OutputPort {
+    fn write_byte(&mut self, value: u8) -> Result<()>;
+}
+
+fn set_pin<P: OutputPin>(pin: &mut P, value: u8, bit: u8) -> Result<()> {
+    if value & (1 << bit) != 0 {
+        pin.set_high().map_err(Error::pin)
+    } else {
+        pin.set_low().map_err(Error::pin)
+    }
+}
+
+impl<
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > OutputPort for (D0, D1, D2, D3, D4, D5, D6, D7)
+{
+    fn write_byte(&mut self, value: u8) -> Result<()> {
+        set_pin(&mut self.0, value, 0)?;
+        set_pin(&mut self.1, value, 1)?;
+        set_pin(&mut self.2, value, 2)?;
+        set_pin(&mut self.3, value, 3)?;
+        set_pin(&mut self.4, value, 4)?;
+        set_pin(&mut self.5, value, 5)?;
+        set_pin(&mut self.6, value, 6)?;
+        set_pin(&mut self.7, value, 7)?;
+
+        Ok(())
+    }
+}
+
+impl<P: OutputPin> OutputPort for [P; 8] {
+    fn write_byte(&mut self, value: u8) -> Result<()> {
+        for (bit, pin) in self.iter_mut().enumerate() {
+            set_pin(pin, value, bit as u8)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An 8-bit bus that puts each byte on the data lines with a single write to
+/// an [OutputPort](trait.OutputPort.html)
+pub struct PortBus<RS: OutputPin, EN: OutputPin, P: OutputPort> {
+    rs: RS,
+    en: EN,
+    port: P,
+    line: LineCharacteristics,
+    timing: TimingConfig,
+}
+
+impl<RS: OutputPin, EN: OutputPin, P: OutputPort> PortBus<RS, EN, P> {
+    pub fn from_port(rs: RS, en: EN, port: P) -> PortBus<RS, EN, P> {
+        let mut bus = PortBus {
+            rs,
+            en,
+            port,
+            line: LineCharacteristics::default(),
+            timing: TimingConfig::default(),
+        };
+        bus.set_timing(&TimingConfig::default());
+        bus
+    }
+
+    /// Set how slowly the lines settle so the transfer timing can allow for
+    /// it, see [LineCharacteristics](struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.line = line;
+    }
+
+    /// Time in microseconds the strobe of a byte takes
+    pub(crate) fn byte_transfer_us(&self) -> u32 {
+        self.line.strobe_us(&self.timing)
+    }
+}
+
+impl<RS: OutputPin, EN: OutputPin, P: OutputPort> DataBus for PortBus<RS, EN, P> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        if data {
+            self.rs.set_high().map_err(Error::pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::pin)?;
+        }
+
+        self.port.write_byte(byte)?;
+        self.line.data_setup(delay);
+
+        self.en.set_high().map_err(Error::pin)?;
+        self.timing.enable_pulse(delay);
+        self.line.enable_pulse(delay);
+        self.en.set_low().map_err(Error::pin)?;
+        self.line.enable_hold(delay);
+
+        if data {
+            self.rs.set_low().map_err(Error::pin)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.timing = TimingConfig {
+            enable_pulse_us: timing.enable_pulse_us.or(Some(ENABLE_PULSE_US)),
+            ..*timing
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{Delay, Event, EventLog, PinLog};
+    use crate::HD44780;
+
+    const DATA: [&str; 8] = ["d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7"];
+
+    /// A port that records every value written to it
+    #[derive(Clone, Default)]
+    struct Port(Rc<RefCell<Vec<u8>>>);
+
+    impl OutputPort for Port {
+        fn write_byte(&mut self, value: u8) -> Result<()> {
+            self.0.borrow_mut().push(value);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn one_port_write_per_byte() {
+        let log = PinLog::new();
+        let port = Port::default();
+        let mut lcd =
+            HD44780::new_port(log.pin("rs"), log.pin("en"), port.clone(), &mut Delay).unwrap();
+        port.0.borrow_mut().clear();
+
+        lcd.write_str("Hi", &mut Delay).unwrap();
+
+        assert_eq!(*port.0.borrow(), b"Hi");
+    }
+
+    #[test]
+    fn init_matches_eight_bit_pins() {
+        let port_log = PinLog::new();
+        let pins = DATA.map(|name| port_log.pin(name));
+        HD44780::new_port(port_log.pin("rs"), port_log.pin("en"), pins, &mut Delay).unwrap();
+
+        let pin_log = PinLog::new();
+        HD44780::new_8bit(
+            pin_log.pin("rs"),
+            pin_log.pin("en"),
+            pin_log.pin("d0"),
+            pin_log.pin("d1"),
+            pin_log.pin("d2"),
+            pin_log.pin("d3"),
+            pin_log.pin("d4"),
+            pin_log.pin("d5"),
+            pin_log.pin("d6"),
+            pin_log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        assert_eq!(
+            port_log.latched("en", "rs", &DATA),
+            pin_log.latched("en", "rs", &DATA)
+        );
+    }
+
+    fn delays(log: &EventLog) -> Vec<u32> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::DelayUs(us) => Some(us),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enable_pulse_follows_the_timing() {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = PortBus::from_port(pins.pin("rs"), pins.pin("en"), Port::default());
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+        bus.set_timing(&TimingConfig::relaxed());
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+        bus.set_timing(&TimingConfig {
+            enable_pulse_us: Some(3),
+            ..TimingConfig::default()
+        });
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [1, 1, 3]);
+    }
+
+    #[test]
+    fn timing_covers_slow_edges() {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = PortBus::from_port(pins.pin("rs"), pins.pin("en"), Port::default());
+        bus.set_line_characteristics(LineCharacteristics {
+            rise_time_us: 5,
+            fall_time_us: 3,
+        });
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [6, 1, 5, 3]);
+        assert_eq!(bus.byte_transfer_us(), 15);
+    }
+
+    #[test]
+    fn tuple_of_pins_maps_bits_in_order() {
+        let log = PinLog::new();
+        let mut port = (
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+
+        port.write_byte(0b1000_0101).unwrap();
+
+        assert_eq!(
+            log.events(),
+            [
+                ("d0", true),
+                ("d1", false),
+                ("d2", true),
+                ("d3", false),
+                ("d4", false),
+                ("d5", false),
+                ("d6", false),
+                ("d7", true),
+            ]
+        );
+    }
+}
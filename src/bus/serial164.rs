@@ -0,0 +1,179 @@
+use crate::delay::DelayNs;
+use crate::digital::OutputPin;
+
+use crate::{
+    bus::{reset_by_nibbles, DataBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// A 4-bit bus through a 74HC164 shift register using only two pins, data
+/// and clock, where the `HD44780` enable line is the data pin ANDed with the
+/// last register output (through a diode and pull-up).
+///
+/// The register outputs are wired as:
+///
+/// | QA | QB | QC | QD | QE | QF | QG | QH   |
+/// |----|----|----|----|----|----|----|------|
+/// | -  | -  | D4 | D5 | D6 | D7 | RS | Gate |
+///
+/// For every nibble the register is first cleared with the data pin low, so
+/// enable can't be raised while bits are moving through it. The gate bit,
+/// register select and nibble are then shifted in, and raising and lowering
+/// the data pin strobes enable.
+///
+/// The clock is toggled as fast as the pins allow, which assumes a clock
+/// period of at least 50ns (well within the 74HC164's limits at 2V and up).
+pub struct SerialBus164<DATA: OutputPin, CLOCK: OutputPin> {
+    data: DATA,
+    clock: CLOCK,
+}
+
+const GATE: u8 = 0b1000_0000;
+const REGISTER_SELECT: u8 = 0b0100_0000;
+const DATA_SHIFT: u8 = 2;
+
+impl<DATA: OutputPin, CLOCK: OutputPin> SerialBus164<DATA, CLOCK> {
+    pub fn from_pins(data: DATA, clock: CLOCK) -> SerialBus164<DATA, CLOCK> {
+        SerialBus164 { data, clock }
+    }
+
+    /// Shift `bits` into the register, most significant bit first
+    fn shift_out(&mut self, bits: u8) -> Result<()> {
+        for i in (0..8).rev() {
+            if bits & (1 << i) != 0 {
+                self.data.set_high().map_err(Error::pin)?;
+            } else {
+                self.data.set_low().map_err(Error::pin)?;
+            }
+
+            self.clock.set_high().map_err(Error::pin)?;
+            self.clock.set_low().map_err(Error::pin)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the lower 4 bits of `nibble` to the lcd
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        let rs = match data {
+            false => 0u8,
+            true => REGISTER_SELECT,
+        };
+
+        // Empty the register so the gate bit is low until it's shifted in last
+        self.shift_out(0)?;
+        self.shift_out(GATE | rs | ((nibble & 0x0F) << DATA_SHIFT))?;
+
+        // With the gate open, the data pin drives the enable line
+        self.data.set_high().map_err(Error::pin)?;
+        delay.delay_ms(2);
+        self.data.set_low().map_err(Error::pin)?;
+
+        Ok(())
+    }
+}
+
+impl<DATA: OutputPin, CLOCK: OutputPin> DataBus for SerialBus164<DATA, CLOCK> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_nibble(byte >> 4, data, delay)?;
+        self.write_nibble(byte & 0x0F, data, delay)?;
+
+        Ok(())
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            self.write_nibble(nibble, false, delay)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{Delay, PinLog};
+    use crate::HD44780;
+
+    /// Replay the pin changes through a model of the register and the enable
+    /// gate, returning what the `HD44780` latches as `(rs, nibble)`
+    fn latched_nibbles(log: &PinLog) -> Vec<(bool, u8)> {
+        let mut register = 0u8;
+        let mut data = false;
+        let mut enable = false;
+        let mut latched = Vec::new();
+
+        for (pin, level) in log.events() {
+            match pin {
+                "data" => data = level,
+                "clock" if level => register = (register << 1) | data as u8,
+                _ => {}
+            }
+
+            let gated = data && register & GATE != 0;
+            if enable && !gated {
+                latched.push((
+                    register & REGISTER_SELECT != 0,
+                    (register >> DATA_SHIFT) & 0x0F,
+                ));
+            }
+            enable = gated;
+        }
+
+        latched
+    }
+
+    /// The bits shifted in on each rising clock edge
+    fn shifted_bits(log: &PinLog) -> Vec<bool> {
+        let mut data = false;
+        let mut bits = Vec::new();
+
+        for (pin, level) in log.events() {
+            match pin {
+                "data" => data = level,
+                "clock" if level => bits.push(data),
+                _ => {}
+            }
+        }
+
+        bits
+    }
+
+    #[test]
+    fn bit_stream_for_a_byte() {
+        let log = PinLog::new();
+        let mut bus = SerialBus164::from_pins(log.pin("data"), log.pin("clock"));
+
+        bus.write(0xA5, true, &mut Delay).unwrap();
+
+        let bits: Vec<u8> = shifted_bits(&log).into_iter().map(|b| b as u8).collect();
+        assert_eq!(
+            bits,
+            [
+                0, 0, 0, 0, 0, 0, 0, 0, // clear
+                1, 1, 1, 0, 1, 0, 0, 0, // gate, rs, 0xA
+                0, 0, 0, 0, 0, 0, 0, 0, // clear
+                1, 1, 0, 1, 0, 1, 0, 0, // gate, rs, 0x5
+            ]
+        );
+        assert_eq!(latched_nibbles(&log), [(true, 0x0A), (true, 0x05)]);
+    }
+
+    #[test]
+    fn enable_never_strobes_while_shifting() {
+        let log = PinLog::new();
+        let mut lcd =
+            HD44780::new_serial_164(log.pin("data"), log.pin("clock"), &mut Delay).unwrap();
+
+        lcd.write_str("Hi", &mut Delay).unwrap();
+
+        let nibbles = latched_nibbles(&log);
+        let data: Vec<(bool, u8)> = nibbles.into_iter().filter(|&(rs, _)| rs).collect();
+
+        assert_eq!(data, [(true, 0x4), (true, 0x8), (true, 0x6), (true, 0x9)]);
+    }
+}
@@ -0,0 +1,345 @@
+use core::cell::{RefCell, RefMut};
+
+use crate::delay::DelayNs;
+use crate::digital::OutputPin;
+
+use crate::bus::fourbit::FourBitLines;
+use crate::bus::{reset_by_nibbles, BusHooks, DataBus, LineCharacteristics, PinMap};
+use crate::error::{Error, Result};
+use crate::timing::TimingConfig;
+
+/// Owns the register select and data pins that several displays share on a
+/// 4-bit bus where only the enable pin differs between displays.
+///
+/// Each display gets its own [SharedFourBitBus](struct.SharedFourBitBus.html)
+/// handle from [bus](#method.bus), which can then be handed to
+/// `HD44780::new_shared` to build fully independent drivers.
+///
+/// ```rust,ignore
+/// let manager = SharedBusManager::new(rs, d4, d5, d6, d7);
+///
+/// let mut top = HD44780::new_shared(manager.bus(en_top), &mut delay)?;
+/// let mut bottom = HD44780::new_shared(manager.bus(en_bottom), &mut delay)?;
+///
+/// top.write_str("Top", &mut delay)?;
+/// bottom.write_str("Bottom", &mut delay)?;
+/// ```
+pub struct SharedBusManager<
+    RS: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+> {
+    lines: RefCell<FourBitLines<RS, D4, D5, D6, D7>>,
+}
+
+impl<RS: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
+    SharedBusManager<RS, D4, D5, D6, D7>
+{
+    pub fn new(rs: RS, d4: D4, d5: D5, d6: D6, d7: D7) -> SharedBusManager<RS, D4, D5, D6, D7> {
+        SharedBusManager::new_with_map(rs, d4, d5, d6, d7, PinMap::Straight)
+    }
+
+    /// Share data pins that aren't wired to DB4 to DB7 in order, see
+    /// [PinMap](enum.PinMap.html)
+    pub fn new_with_map(
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        map: PinMap,
+    ) -> SharedBusManager<RS, D4, D5, D6, D7> {
+        SharedBusManager {
+            lines: RefCell::new(FourBitLines::new(rs, d4, d5, d6, d7, map)),
+        }
+    }
+
+    /// Create a handle to the shared pins for the display wired to `en`
+    pub fn bus<EN: OutputPin>(&self, en: EN) -> SharedFourBitBus<'_, RS, EN, D4, D5, D6, D7> {
+        SharedFourBitBus {
+            lines: &self.lines,
+            en,
+            hooks: BusHooks::default(),
+            line: LineCharacteristics::default(),
+            timing: TimingConfig::default(),
+        }
+    }
+}
+
+/// A 4-bit bus to a single display that borrows its register select and data
+/// pins from a [SharedBusManager](struct.SharedBusManager.html).
+///
+/// The shared pins are only held for the duration of a single byte transfer,
+/// so a transfer to one display can never be interleaved with another, while
+/// the wait for a slow command (such as clear) on one display doesn't stop
+/// the other displays from being written to. Transfers are clocked out the
+/// same way as on a [FourBitBus](struct.FourBitBus.html), with this
+/// display's own hooks, line characteristics and timing.
+///
+/// Writes never yield partway through a byte, so displays driven from
+/// different tasks on the same executor can't interleave their nibbles. The
+/// handles aren't `Sync`, so sharing them between executors or interrupt
+/// priorities needs a critical section around each write.
+pub struct SharedFourBitBus<
+    'a,
+    RS: OutputPin,
+    EN: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+> {
+    lines: &'a RefCell<FourBitLines<RS, D4, D5, D6, D7>>,
+    en: EN,
+    hooks: BusHooks,
+    line: LineCharacteristics,
+    timing: TimingConfig,
+}
+
+impl<
+        'a,
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > SharedFourBitBus<'a, RS, EN, D4, D5, D6, D7>
+{
+    /// Set the functions called at each step of a transfer to this display,
+    /// see [BusHooks](struct.BusHooks.html)
+    pub fn set_hooks(&mut self, hooks: BusHooks) {
+        self.hooks = hooks;
+    }
+
+    /// Set how slowly the lines to this display settle so the transfer
+    /// timing can allow for it, see
+    /// [LineCharacteristics](struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.line = line;
+    }
+
+    /// Time in microseconds the two strobes of a byte take
+    pub(crate) fn byte_transfer_us(&self) -> u32 {
+        2 * self.line.strobe_us(&self.timing)
+    }
+
+    /// Hold the shared pins for one transfer, clocked the way this display
+    /// wants
+    fn borrow_lines(&self) -> Result<RefMut<'a, FourBitLines<RS, D4, D5, D6, D7>>> {
+        let mut lines = self.lines.try_borrow_mut().map_err(|_| Error::Bus)?;
+
+        lines.hooks = self.hooks;
+        lines.line = self.line;
+        lines.timing = self.timing;
+
+        Ok(lines)
+    }
+}
+
+impl<
+        'a,
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > DataBus for SharedFourBitBus<'a, RS, EN, D4, D5, D6, D7>
+{
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        // Hold the shared pins for the whole byte so that another display's
+        // transfer can't land between our two nibbles
+        let mut lines = self.borrow_lines()?;
+
+        lines.write_byte(&mut self.en, byte, data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.timing = *timing;
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            let mut lines = self.borrow_lines()?;
+
+            lines.write_nibble(&mut self.en, nibble, false, delay)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::bus::FourBitBus;
+    use crate::mock::{Delay, Event, EventLog, PinLog};
+    use crate::HD44780;
+
+    const DATA: [&str; 4] = ["d4", "d5", "d6", "d7"];
+
+    fn data_bytes(log: &PinLog, en: &str) -> Vec<u8> {
+        log.bytes_4bit(en, "rs", DATA)
+            .into_iter()
+            .filter(|&(rs, _)| rs)
+            .map(|(_, byte)| byte)
+            .collect()
+    }
+
+    #[test]
+    fn displays_receive_only_their_own_writes() {
+        let log = PinLog::new();
+        let manager = SharedBusManager::new(
+            log.pin("rs"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+
+        let mut top = HD44780::new_shared(manager.bus(log.pin("en1")), &mut Delay).unwrap();
+        let mut bottom = HD44780::new_shared(manager.bus(log.pin("en2")), &mut Delay).unwrap();
+
+        top.write_str("Top", &mut Delay).unwrap();
+        bottom.write_str("Bottom", &mut Delay).unwrap();
+        top.write_str("!", &mut Delay).unwrap();
+
+        assert_eq!(data_bytes(&log, "en1"), b"Top!");
+        assert_eq!(data_bytes(&log, "en2"), b"Bottom");
+    }
+
+    #[test]
+    fn three_displays_share_one_set_of_pins() {
+        let log = PinLog::new();
+        let manager = SharedBusManager::new(
+            log.pin("rs"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+
+        let mut first = HD44780::new_shared(manager.bus(log.pin("en1")), &mut Delay).unwrap();
+        let mut second = HD44780::new_shared(manager.bus(log.pin("en2")), &mut Delay).unwrap();
+        let mut third = HD44780::new_shared(manager.bus(log.pin("en3")), &mut Delay).unwrap();
+
+        for (i, word) in ["one", "two", "three"].iter().enumerate() {
+            first
+                .write_char(word.as_bytes()[0] as char, &mut Delay)
+                .unwrap();
+            second.write_str(word, &mut Delay).unwrap();
+            third.write_bytes(&[b'0' + i as u8], &mut Delay).unwrap();
+        }
+
+        assert_eq!(data_bytes(&log, "en1"), b"ott");
+        assert_eq!(data_bytes(&log, "en2"), b"onetwothree");
+        assert_eq!(data_bytes(&log, "en3"), b"012");
+    }
+
+    #[test]
+    fn both_displays_are_initialized() {
+        let log = PinLog::new();
+        let manager = SharedBusManager::new(
+            log.pin("rs"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+        );
+
+        let _top = HD44780::new_shared(manager.bus(log.pin("en1")), &mut Delay).unwrap();
+        let _bottom = HD44780::new_shared(manager.bus(log.pin("en2")), &mut Delay).unwrap();
+
+        assert_eq!(
+            log.bytes_4bit("en1", "rs", DATA),
+            log.bytes_4bit("en2", "rs", DATA)
+        );
+        assert!(!log.bytes_4bit("en1", "rs", DATA).is_empty());
+    }
+
+    fn delays(log: &EventLog) -> Vec<u32> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::DelayUs(us) => Some(us),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn handles_clock_bytes_out_like_a_four_bit_bus() {
+        let line = LineCharacteristics {
+            rise_time_us: 5,
+            fall_time_us: 3,
+        };
+
+        let pins = PinLog::new();
+        let manager = SharedBusManager::new(
+            pins.pin("rs"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+        );
+        let mut tuned = manager.bus(pins.pin("en1"));
+        tuned.set_timing(&TimingConfig::fast());
+        tuned.set_line_characteristics(line);
+        let mut plain = manager.bus(pins.pin("en2"));
+
+        let own_pins = PinLog::new();
+        let mut four_bit = FourBitBus::from_pins(
+            own_pins.pin("rs"),
+            own_pins.pin("en"),
+            own_pins.pin("d4"),
+            own_pins.pin("d5"),
+            own_pins.pin("d6"),
+            own_pins.pin("d7"),
+        );
+        four_bit.set_timing(&TimingConfig::fast());
+        four_bit.set_line_characteristics(line);
+
+        let shared = EventLog::new();
+        tuned.write(0x41, true, &mut shared.delay()).unwrap();
+        let own = EventLog::new();
+        four_bit.write(0x41, true, &mut own.delay()).unwrap();
+        assert_eq!(delays(&shared), delays(&own));
+
+        shared.clear();
+        plain.write(0x41, true, &mut shared.delay()).unwrap();
+        assert_eq!(delays(&shared), [2000, 2000]);
+
+        assert_eq!(
+            pins.bytes_4bit("en1", "rs", DATA),
+            own_pins.bytes_4bit("en", "rs", DATA)
+        );
+    }
+
+    #[test]
+    fn handles_follow_the_pin_map() {
+        let log = PinLog::new();
+        let manager = SharedBusManager::new_with_map(
+            log.pin("rs"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            PinMap::Reversed,
+        );
+
+        manager
+            .bus(log.pin("en"))
+            .write(0b0011_0001, true, &mut Delay)
+            .unwrap();
+
+        assert_eq!(
+            log.latched("en", "rs", &DATA),
+            [(true, 0b1100), (true, 0b1000)]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use crate::delay::DelayNs;
+use crate::digital::OutputPin;
+use embedded_hal_0_2::blocking::spi;
+
+use crate::{
+    bus::{reset_by_nibbles, DataBus},
+    error::{Error, Result},
+    timing::TimingConfig,
+};
+
+/// Which 74HC595 output (`0` for QA to `7` for QH) each `HD44780` line is
+/// wired to. Bytes are shifted out most significant bit first, so bit `n`
+/// of a byte ends up on output `n`.
+///
+/// The default matches the Adafruit I2C/SPI LCD backpack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShiftRegisterPinout {
+    pub rs: u8,
+    pub en: u8,
+    pub d4: u8,
+    pub d5: u8,
+    pub d6: u8,
+    pub d7: u8,
+    /// The output switching the backlight, which is kept on
+    pub backlight: Option<u8>,
+}
+
+impl Default for ShiftRegisterPinout {
+    fn default() -> ShiftRegisterPinout {
+        ShiftRegisterPinout {
+            rs: 1,
+            en: 2,
+            d4: 3,
+            d5: 4,
+            d6: 5,
+            d7: 6,
+            backlight: Some(7),
+        }
+    }
+}
+
+impl ShiftRegisterPinout {
+    /// Whether every line is on one of the register's eight outputs
+    fn fits(&self) -> bool {
+        [self.rs, self.en, self.d4, self.d5, self.d6, self.d7]
+            .iter()
+            .chain(self.backlight.iter())
+            .all(|&pin| pin < 8)
+    }
+
+    /// The register outputs for the lower 4 bits of `nibble` with enable low
+    fn outputs(&self, nibble: u8, data: bool) -> u8 {
+        let mut outputs = 0;
+
+        for (bit, &pin) in [self.d4, self.d5, self.d6, self.d7].iter().enumerate() {
+            if nibble & (1 << bit) != 0 {
+                outputs |= 1 << pin;
+            }
+        }
+
+        if data {
+            outputs |= 1 << self.rs;
+        }
+
+        if let Some(backlight) = self.backlight {
+            outputs |= 1 << backlight;
+        }
+
+        outputs
+    }
+}
+
+/// A 4-bit bus through a 74HC595 shift register clocked over SPI, with its
+/// storage register clock on a separate latch pin, which needs only three
+/// pins on the microcontroller.
+pub struct ShiftRegisterBus<SPI: spi::Write<u8>, LATCH: OutputPin> {
+    spi: SPI,
+    latch: LATCH,
+    pinout: ShiftRegisterPinout,
+    // What the register is currently outputting, if known
+    last: Option<u8>,
+}
+
+impl<SPI: spi::Write<u8>, LATCH: OutputPin> ShiftRegisterBus<SPI, LATCH> {
+    /// A pinout naming an output past `7` returns `Error::OutOfBounds`
+    pub fn new(
+        spi: SPI,
+        latch: LATCH,
+        pinout: ShiftRegisterPinout,
+    ) -> Result<ShiftRegisterBus<SPI, LATCH>> {
+        if !pinout.fits() {
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(ShiftRegisterBus {
+            spi,
+            latch,
+            pinout,
+            last: None,
+        })
+    }
+
+    /// Shift `outputs` into the register and latch them onto its outputs
+    fn set_outputs(&mut self, outputs: u8) -> Result<()> {
+        self.spi.write(&[outputs]).map_err(|_| Error::Bus)?;
+
+        self.latch.set_high().map_err(Error::pin)?;
+        self.latch.set_low().map_err(Error::pin)?;
+
+        self.last = Some(outputs);
+
+        Ok(())
+    }
+
+    /// Write the lower 4 bits of `nibble` to the lcd
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, data: bool, delay: &mut D) -> Result<()> {
+        let outputs = self.pinout.outputs(nibble, data);
+
+        // Register select and the data lines have to be stable before enable
+        // rises, which they already are if the last nibble was the same
+        if self.last != Some(outputs) {
+            self.set_outputs(outputs)?;
+        }
+
+        self.set_outputs(outputs | (1 << self.pinout.en))?;
+        delay.delay_ms(2);
+        self.set_outputs(outputs)?;
+
+        Ok(())
+    }
+}
+
+impl<SPI: spi::Write<u8>, LATCH: OutputPin> DataBus for ShiftRegisterBus<SPI, LATCH> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.write_nibble(byte >> 4, data, delay)?;
+        self.write_nibble(byte & 0x0F, data, delay)?;
+
+        Ok(())
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        reset_by_nibbles(timing, delay, |nibble, delay| {
+            self.write_nibble(nibble, false, delay)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::convert::Infallible;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::Delay;
+    use crate::HD44780;
+
+    #[derive(Clone, Default)]
+    struct Register {
+        shifted: Rc<RefCell<u8>>,
+        latched: Rc<RefCell<Vec<u8>>>,
+    }
+
+    impl spi::Write<u8> for Register {
+        type Error = Infallible;
+
+        fn write(&mut self, words: &[u8]) -> core::result::Result<(), Infallible> {
+            for &word in words {
+                *self.shifted.borrow_mut() = word;
+            }
+            Ok(())
+        }
+    }
+
+    impl embedded_hal::digital::ErrorType for Register {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Register {
+        fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+            let shifted = *self.shifted.borrow();
+            self.latched.borrow_mut().push(shifted);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn enable_is_latched_high_then_low_for_each_nibble() {
+        let register = Register::default();
+        let mut bus = ShiftRegisterBus::new(
+            register.clone(),
+            register.clone(),
+            ShiftRegisterPinout::default(),
+        )
+        .unwrap();
+
+        bus.write(0xA5, true, &mut Delay).unwrap();
+
+        assert_eq!(
+            *register.latched.borrow(),
+            [
+                0b1101_0010,
+                0b1101_0110,
+                0b1101_0010,
+                0b1010_1010,
+                0b1010_1110,
+                0b1010_1010,
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_nibble_skips_setup() {
+        let register = Register::default();
+        let mut bus = ShiftRegisterBus::new(
+            register.clone(),
+            register.clone(),
+            ShiftRegisterPinout::default(),
+        )
+        .unwrap();
+
+        bus.write(0x33, false, &mut Delay).unwrap();
+
+        assert_eq!(
+            *register.latched.borrow(),
+            [
+                0b1001_1000,
+                0b1001_1100,
+                0b1001_1000,
+                0b1001_1100,
+                0b1001_1000,
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_pinout() {
+        let register = Register::default();
+        let mut bus = ShiftRegisterBus::new(
+            register.clone(),
+            register.clone(),
+            ShiftRegisterPinout {
+                rs: 7,
+                en: 6,
+                d4: 0,
+                d5: 1,
+                d6: 2,
+                d7: 3,
+                backlight: None,
+            },
+        )
+        .unwrap();
+
+        bus.write(0x1F, true, &mut Delay).unwrap();
+
+        assert_eq!(
+            *register.latched.borrow(),
+            [
+                0b1000_0001,
+                0b1100_0001,
+                0b1000_0001,
+                0b1000_1111,
+                0b1100_1111,
+                0b1000_1111,
+            ]
+        );
+    }
+
+    #[test]
+    fn outputs_past_the_register_are_refused() {
+        let register = Register::default();
+        let pinout = ShiftRegisterPinout {
+            backlight: Some(8),
+            ..ShiftRegisterPinout::default()
+        };
+
+        assert!(matches!(
+            ShiftRegisterBus::new(register.clone(), register.clone(), pinout),
+            Err(Error::OutOfBounds)
+        ));
+
+        let pinout = ShiftRegisterPinout {
+            en: 12,
+            ..ShiftRegisterPinout::default()
+        };
+
+        assert!(matches!(
+            HD44780::new_shift_register(register.clone(), register.clone(), pinout, &mut Delay),
+            Err(Error::OutOfBounds)
+        ));
+        assert_eq!(*register.latched.borrow(), []);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::delay::DelayNs;
+use embedded_hal_0_2::blocking::i2c::Write;
+
+use crate::{
+    bus::DataBus,
+    error::{Error, Result},
+};
+
+/// A bus to an ST7032i based display, which takes HD44780 instructions
+/// directly over I2C rather than through a port expander. Every byte is
+/// preceded by a control byte saying whether it is a command or data.
+pub struct St7032Bus<I2C: Write> {
+    i2c_bus: I2C,
+    address: u8,
+}
+
+const COMMAND: u8 = 0x00;
+const DATA: u8 = 0x40;
+
+impl<I2C: Write> St7032Bus<I2C> {
+    pub fn new(i2c_bus: I2C, address: u8) -> St7032Bus<I2C> {
+        St7032Bus { i2c_bus, address }
+    }
+}
+
+impl<I2C: Write> DataBus for St7032Bus<I2C> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, _delay: &mut D) -> Result<()> {
+        let control = match data {
+            false => COMMAND,
+            true => DATA,
+        };
+
+        self.i2c_bus
+            .write(self.address, &[control, byte])
+            .map_err(|_| Error::Bus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::mock::{Delay, I2cLog};
+    use crate::{DisplayMode, HD44780};
+
+    const ADDRESS: u8 = 0x3E;
+
+    fn commands(log: &I2cLog) -> Vec<u8> {
+        log.writes()
+            .into_iter()
+            .filter(|(_, bytes)| bytes[0] == 0x00)
+            .map(|(_, bytes)| bytes[1])
+            .collect()
+    }
+
+    #[test]
+    fn control_bytes() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_st7032(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.clear(&mut Delay).unwrap();
+        lcd.write_str("Hi", &mut Delay).unwrap();
+
+        assert_eq!(
+            log.writes(),
+            [
+                (ADDRESS, vec![0x00, 0x01]),
+                (ADDRESS, vec![0x40, b'H']),
+                (ADDRESS, vec![0x40, b'i']),
+            ]
+        );
+    }
+
+    #[test]
+    fn extended_init() {
+        let log = I2cLog::new();
+        HD44780::new_st7032(log.i2c(), ADDRESS, &mut Delay).unwrap();
+
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(
+            commands(&log),
+            [0x38, 0x39, 0x14, 0x70, 0x56, 0x6C, 0x38, 0x08, 0x01, 0x06, on]
+        );
+    }
+
+    #[test]
+    fn contrast_is_split_across_two_commands() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_st7032(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.set_contrast(0b10_1101, &mut Delay).unwrap();
+        assert_eq!(commands(&log), [0x39, 0x7D, 0x56, 0x38]);
+
+        log.clear();
+        lcd.set_contrast(200, &mut Delay).unwrap();
+        assert_eq!(commands(&log), [0x39, 0x7F, 0x57, 0x38]);
+    }
+
+    #[test]
+    fn contrast_keeps_the_lines() {
+        let log = I2cLog::new();
+        let mut lcd = HD44780::new_st7032(log.i2c(), ADDRESS, &mut Delay).unwrap();
+        lcd.set_lines(crate::Lines::One, &mut Delay).unwrap();
+        log.clear();
+
+        lcd.set_contrast(0b10_1101, &mut Delay).unwrap();
+        assert_eq!(commands(&log), [0x31, 0x7D, 0x56, 0x30]);
+    }
+}
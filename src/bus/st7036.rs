@@ -0,0 +1,182 @@
+use crate::delay::DelayNs;
+
+use crate::{
+    bus::{ActiveController, DataBus},
+    error::Result,
+    timing::TimingConfig,
+};
+
+/// Wraps the bus to an ST7036 based display, such as the EA DOGM series,
+/// so the driver can switch between the ST7036's instruction tables and
+/// back.
+pub struct St7036Bus<B> {
+    bus: B,
+}
+
+impl<B: DataBus> St7036Bus<B> {
+    /// Wrap `bus`
+    pub fn from_bus(bus: B) -> St7036Bus<B> {
+        St7036Bus { bus }
+    }
+
+    /// Give back the wrapped bus
+    pub fn destroy(self) -> B {
+        self.bus
+    }
+}
+
+impl<B: DataBus> DataBus for St7036Bus<B> {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, delay: &mut D) -> Result<()> {
+        self.bus.write(byte, data, delay)
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8> {
+        self.bus.read(data, delay)
+    }
+
+    fn set_timing(&mut self, timing: &TimingConfig) {
+        self.bus.set_timing(timing)
+    }
+
+    fn reset_4bit<D: DelayNs>(&mut self, timing: &TimingConfig, delay: &mut D) -> Result<()> {
+        self.bus.reset_4bit(timing, delay)
+    }
+
+    fn select_row(&mut self, row: u8) {
+        self.bus.select_row(row)
+    }
+
+    fn selected_controller(&self) -> Option<ActiveController> {
+        self.bus.selected_controller()
+    }
+
+    fn select_controller(&mut self, controller: ActiveController) {
+        self.bus.select_controller(controller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::mock::{self, Delay, Event, EventLog};
+    use crate::{Config, DisplayMode, Init, HD44780};
+
+    fn lcd<B: DataBus>(bus: B) -> HD44780<St7036Bus<B>> {
+        HD44780::from_parts(
+            St7036Bus::from_bus(bus),
+            Init::St7036(false),
+            Config::default(),
+        )
+        .unwrap()
+    }
+
+    fn commands(log: &EventLog) -> Vec<u8> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Command(cmd) => Some(cmd),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn init_sets_up_the_booster_and_follower() {
+        let log = EventLog::new();
+        lcd(log.bus()).init_st7036(&mut log.delay()).unwrap();
+
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(
+            commands(&log),
+            [0x29, 0x14, 0x55, 0x6D, 0x78, 0x28, 0x08, 0x01, 0x06, on]
+        );
+    }
+
+    #[test]
+    fn contrast_is_split_across_table_1() {
+        let log = EventLog::new();
+        let mut lcd = lcd(log.bus());
+
+        lcd.set_contrast(0b10_1101, &mut log.delay()).unwrap();
+        lcd.set_contrast(200, &mut log.delay()).unwrap();
+
+        assert_eq!(
+            commands(&log),
+            [0x29, 0x7D, 0x56, 0x28, 0x29, 0x7F, 0x57, 0x28]
+        );
+    }
+
+    #[test]
+    fn contrast_keeps_the_lines_and_font() {
+        let log = EventLog::new();
+        let mut lcd = lcd(log.bus());
+        lcd.set_lines(crate::Lines::One, &mut log.delay()).unwrap();
+        lcd.set_font(crate::FontMode::FiveByTen, &mut log.delay())
+            .unwrap();
+
+        log.clear();
+        lcd.set_contrast(0b10_1101, &mut log.delay()).unwrap();
+        assert_eq!(commands(&log), [0x25, 0x7D, 0x56, 0x24]);
+    }
+
+    /// A bus that fails the `n`th write and records the rest
+    struct FailsOnce {
+        n: usize,
+        writes: Vec<u8>,
+    }
+
+    impl DataBus for FailsOnce {
+        fn write<D: DelayNs>(&mut self, byte: u8, _data: bool, _delay: &mut D) -> Result<()> {
+            self.n = self.n.wrapping_sub(1);
+            if self.n == 0 {
+                return Err(Error::Bus);
+            }
+
+            self.writes.push(byte);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_contrast_write_still_restores_table_0() {
+        let mut lcd = lcd(FailsOnce {
+            n: 2,
+            writes: Vec::new(),
+        });
+
+        assert_eq!(lcd.set_contrast(32, &mut Delay), Err(Error::Bus));
+        assert_eq!(lcd.bus.bus.writes, [0x29, 0x28]);
+    }
+
+    #[test]
+    fn eight_bit_constructor_uses_the_8bit_function_set() {
+        let log = mock::PinLog::new();
+        let lcd = HD44780::new_8bit_st7036(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d0"),
+            log.pin("d1"),
+            log.pin("d2"),
+            log.pin("d3"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            &mut Delay,
+        )
+        .unwrap();
+
+        assert!(lcd.init.eight_bit());
+        let latched = log.latched(
+            "en",
+            "rs",
+            &["d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7"],
+        );
+        assert_eq!(latched[0], (false, 0x39));
+    }
+}
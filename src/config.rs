@@ -0,0 +1,60 @@
+use crate::controller::ControllerVariant;
+use crate::display_mode::DisplayMode;
+use crate::display_size::DisplaySize;
+use crate::function_set::FontMode;
+use crate::timing::TimingConfig;
+
+/// How to set a display up, for the `new_*_with_config` constructors.
+/// Start from the default and change what differs, so any of the options
+/// can be combined:
+///
+/// ```rust,ignore
+/// let config = Config {
+///     size: DisplaySize::new(20, 4),
+///     ..Config::for_variant(ControllerVariant::Ks0066)
+/// };
+/// let mut lcd = HD44780::new_4bit_with_config(rs, en, d4, d5, d6, d7, config, &mut delay)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Config {
+    /// How many columns and rows the display has, 16x2 by default
+    pub size: DisplaySize,
+    /// The font the function set selects. The 5x10 font only works on one
+    /// row.
+    pub font: FontMode,
+    /// The controller on the display
+    pub variant: ControllerVariant,
+    /// The waits from power up on, see [TimingConfig](../timing/struct.TimingConfig.html)
+    pub timing: TimingConfig,
+    /// The display mode sent at the end of the set up. With the display off
+    /// it stays dark through the set up, and nothing shows until
+    /// `set_display(Display::On, ..)`:
+    ///
+    /// ```rust,ignore
+    /// let config = Config {
+    ///     display_mode: DisplayMode {
+    ///         display: Display::Off,
+    ///         ..DisplayMode::default()
+    ///     },
+    ///     ..Config::default()
+    /// };
+    /// let mut lcd = HD44780::new_4bit_with_config(rs, en, d4, d5, d6, d7, config, &mut delay)?;
+    /// lcd.write_str("Ready", &mut delay)?;
+    /// lcd.set_display(Display::On, &mut delay)?;
+    /// ```
+    pub display_mode: DisplayMode,
+}
+
+impl Config {
+    /// The default set up, timed for `variant` from power up on
+    pub fn for_variant(variant: ControllerVariant) -> Config {
+        Config {
+            variant,
+            timing: TimingConfig {
+                init: variant.init_timing(),
+                ..TimingConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+}
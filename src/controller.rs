@@ -0,0 +1,71 @@
+use crate::timing::InitTiming;
+
+/// The HD44780 compatible controller on the display, for the few places
+/// where clones need different handling from a genuine HD44780U.
+///
+/// They all take the same instructions, but some need longer to power up
+/// or to clear the display, and the KS0066 wants the function set sent
+/// twice during set up.
+///
+/// ```rust,ignore
+/// let mut lcd = HD44780::new_4bit_with_variant(
+///     rs, en, d4, d5, d6, d7,
+///     ControllerVariant::Ks0066,
+///     &mut delay,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ControllerVariant {
+    /// Hitachi HD44780U, and anything that behaves like it
+    #[default]
+    Hd44780,
+    /// Samsung KS0066 / KS0066U
+    Ks0066,
+    /// Sunplus SPLC780 / SPLC780D
+    Splc780,
+    /// Wuxi AiP31066
+    Aip31066,
+    /// Winstar WS0010 / RS0010 OLED controller
+    Ws0010,
+}
+
+impl ControllerVariant {
+    /// The waits while setting the controller up
+    pub fn init_timing(self) -> InitTiming {
+        match self {
+            ControllerVariant::Hd44780 => InitTiming::default(),
+            ControllerVariant::Ks0066 => InitTiming {
+                power_on_ms: 30,
+                function_set_ms: 5,
+                clear_us: 1530,
+            },
+            ControllerVariant::Splc780 => InitTiming::conservative(),
+            ControllerVariant::Aip31066 => InitTiming {
+                power_on_ms: 40,
+                function_set_ms: 5,
+                clear_us: 1640,
+            },
+            ControllerVariant::Ws0010 => InitTiming {
+                clear_us: 6200,
+                ..InitTiming::default()
+            },
+        }
+    }
+
+    /// Time in microseconds that clear display and return home take, when
+    /// the busy flag can't be read
+    pub fn clear_us(self) -> u16 {
+        match self {
+            ControllerVariant::Hd44780 => 1520,
+            ControllerVariant::Ks0066 => 1530,
+            ControllerVariant::Splc780 => 2000,
+            ControllerVariant::Aip31066 => 1640,
+            ControllerVariant::Ws0010 => 6200,
+        }
+    }
+
+    /// Whether the function set is sent a second time during set up
+    pub(crate) fn repeats_function_set(self) -> bool {
+        self == ControllerVariant::Ks0066
+    }
+}
@@ -0,0 +1,64 @@
+//! The delay trait the driver waits with.
+//!
+//! This is the embedded-hal 1.0 `DelayNs` trait. The `eh0` feature adds
+//! [Eh0Delay](struct.Eh0Delay.html), which wraps an embedded-hal 0.2 delay
+//! so it can be passed in too.
+
+pub use embedded_hal::delay::DelayNs;
+
+/// An embedded-hal 0.2 delay, usable wherever the driver takes an
+/// embedded-hal 1.0 `DelayNs`. Waits shorter than a microsecond are rounded
+/// up to one.
+///
+/// ```rust,ignore
+/// let mut delay = Eh0Delay(hal::delay::Delay::new(cp.SYST, clocks));
+/// let mut lcd = HD44780::new_4bit(rs, en, d4, d5, d6, d7, &mut delay)?;
+/// ```
+#[cfg(feature = "eh0")]
+pub struct Eh0Delay<D>(pub D);
+
+#[cfg(feature = "eh0")]
+impl<D: embedded_hal_0_2::blocking::delay::DelayUs<u32>> DelayNs for Eh0Delay<D> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_us(ns.div_ceil(1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        for _ in 0..ms {
+            self.0.delay_us(1000);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "eh0"))]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    struct OldDelay(Vec<u32>);
+
+    impl embedded_hal_0_2::blocking::delay::DelayUs<u32> for OldDelay {
+        fn delay_us(&mut self, us: u32) {
+            self.0.push(us);
+        }
+    }
+
+    #[test]
+    fn eh0_delays_wait_whole_microseconds() {
+        let mut delay = Eh0Delay(OldDelay(Vec::new()));
+
+        delay.delay_ns(1);
+        delay.delay_ns(2000);
+        delay.delay_us(37);
+        delay.delay_ms(2);
+
+        assert_eq!(delay.0 .0, [1, 2, 37, 1000, 1000]);
+    }
+}
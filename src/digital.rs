@@ -0,0 +1,131 @@
+//! The pin traits the buses are bound on.
+//!
+//! These are always the embedded-hal 1.0 `digital` traits. The `eh0` feature
+//! adds [Eh0Pin](struct.Eh0Pin.html), which wraps an embedded-hal 0.2
+//! `digital::v2` pin so it can be passed in too, without changing the
+//! bounds for anyone else.
+
+pub use embedded_hal::digital::{InputPin, OutputPin};
+
+impl embedded_hal::digital::Error for crate::error::Error {
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        match self {
+            crate::error::Error::Pin(kind) => *kind,
+            _ => embedded_hal::digital::ErrorKind::Other,
+        }
+    }
+}
+
+/// An embedded-hal 0.2 `digital::v2` pin, usable wherever the driver takes
+/// an embedded-hal 1.0 pin. Pin errors are reported as
+/// `ErrorKind::Other`.
+///
+/// ```rust,ignore
+/// let mut lcd = HD44780::new_4bit(
+///     Eh0Pin(rs), Eh0Pin(en),
+///     Eh0Pin(d4), Eh0Pin(d5), Eh0Pin(d6), Eh0Pin(d7),
+///     &mut delay,
+/// )?;
+/// ```
+#[cfg(feature = "eh0")]
+pub struct Eh0Pin<P>(pub P);
+
+#[cfg(feature = "eh0")]
+impl<P> embedded_hal::digital::ErrorType for Eh0Pin<P> {
+    type Error = embedded_hal::digital::ErrorKind;
+}
+
+#[cfg(feature = "eh0")]
+impl<P: embedded_hal_0_2::digital::v2::OutputPin> OutputPin for Eh0Pin<P> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0
+            .set_low()
+            .map_err(|_| embedded_hal::digital::ErrorKind::Other)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0
+            .set_high()
+            .map_err(|_| embedded_hal::digital::ErrorKind::Other)
+    }
+}
+
+#[cfg(feature = "eh0")]
+impl<P: embedded_hal_0_2::digital::v2::InputPin> InputPin for Eh0Pin<P> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.0
+            .is_high()
+            .map_err(|_| embedded_hal::digital::ErrorKind::Other)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.0
+            .is_low()
+            .map_err(|_| embedded_hal::digital::ErrorKind::Other)
+    }
+}
+
+#[cfg(all(test, feature = "eh0"))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::bus::{DataBus, FourBitBus};
+    use crate::mock::{Delay, PinLog};
+
+    /// An embedded-hal 0.2 pin that passes its level on to a logged pin
+    struct OldPin(crate::mock::Pin);
+
+    impl embedded_hal_0_2::digital::v2::OutputPin for OldPin {
+        type Error = ();
+
+        fn set_low(&mut self) -> Result<(), ()> {
+            OutputPin::set_low(&mut self.0).map_err(|_| ())
+        }
+
+        fn set_high(&mut self) -> Result<(), ()> {
+            OutputPin::set_high(&mut self.0).map_err(|_| ())
+        }
+    }
+
+    impl embedded_hal_0_2::digital::v2::InputPin for OldPin {
+        type Error = ();
+
+        fn is_high(&self) -> Result<bool, ()> {
+            Err(())
+        }
+
+        fn is_low(&self) -> Result<bool, ()> {
+            Err(())
+        }
+    }
+
+    #[test]
+    fn eh0_pins_drive_a_bus() {
+        let log = PinLog::new();
+        let old = |name| Eh0Pin(OldPin(log.pin(name)));
+        let mut bus = FourBitBus::from_pins(
+            old("rs"),
+            old("en"),
+            old("d4"),
+            old("d5"),
+            old("d6"),
+            old("d7"),
+        );
+
+        bus.write(0x41, true, &mut Delay).unwrap();
+
+        assert_eq!(
+            log.bytes_4bit("en", "rs", ["d4", "d5", "d6", "d7"]),
+            [(true, 0x41)]
+        );
+    }
+
+    #[test]
+    fn eh0_pin_errors_become_other() {
+        let log = PinLog::new();
+        let mut pin = Eh0Pin(OldPin(log.pin("d4")));
+
+        assert_eq!(pin.is_high(), Err(embedded_hal::digital::ErrorKind::Other));
+    }
+}
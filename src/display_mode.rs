@@ -1,16 +1,19 @@
 use crate::{Cursor, CursorBlink, Display};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DisplayMode {
     pub cursor_visibility: Cursor,
     pub cursor_blink: CursorBlink,
     pub display: Display,
 }
 
+/// The display on with the cursor showing but not blinking, which is what
+/// the display is set to when it is constructed
 impl Default for DisplayMode {
     fn default() -> DisplayMode {
         DisplayMode {
             cursor_visibility: Cursor::Visible,
-            cursor_blink: CursorBlink::On,
+            cursor_blink: CursorBlink::Off,
             display: Display::On,
         }
     }
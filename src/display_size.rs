@@ -0,0 +1,89 @@
+/// The DDRAM address ranges, start inclusive and end exclusive, of the two
+/// lines in two line mode. Nothing lives between 0x28 and 0x3F.
+const TWO_LINE_DDRAM: [(u8, u8); 2] = [(0x00, 0x28), (0x40, 0x68)];
+
+/// The DDRAM address range of the only line in one line mode
+const ONE_LINE_DDRAM: [(u8, u8); 1] = [(0x00, 0x50)];
+
+/// How many columns and rows the display has, and the DDRAM address each
+/// row starts at
+///
+/// ```rust,ignore
+/// let mut lcd = HD44780::new_4bit_with_size(
+///     rs, en, d4, d5, d6, d7,
+///     DisplaySize::new(20, 4),
+///     &mut delay,
+/// )?;
+/// lcd.set_cursor_xy(0, 2, &mut delay)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplaySize {
+    pub cols: u8,
+    pub rows: u8,
+    pub line_offsets: [u8; 4],
+}
+
+impl DisplaySize {
+    /// A display of `cols` columns by `rows` rows, up to 4, with the usual
+    /// row addresses. The third and fourth rows of a four row display carry
+    /// on from the end of the first two, so a 20x4 display's rows start at
+    /// 0x00, 0x40, 0x14 and 0x54.
+    ///
+    /// Sizes that don't fit in DDRAM are only rejected once given to the
+    /// driver, which returns `Error::OutOfBounds`.
+    pub const fn new(cols: u8, rows: u8) -> DisplaySize {
+        DisplaySize {
+            cols,
+            rows,
+            line_offsets: [0x00, 0x40, cols, cols.saturating_add(0x40)],
+        }
+    }
+
+    /// A display of `cols` columns by `rows` rows whose rows start at
+    /// `line_offsets`, for modules wired differently from the usual.
+    ///
+    /// Many 16x1 displays are driven as 8x2 internally, with the right half
+    /// of the row at 0x40:
+    ///
+    /// ```rust,ignore
+    /// let size = DisplaySize::with_line_offsets(8, 2, [0x00, 0x40, 0x00, 0x00]);
+    /// ```
+    pub const fn with_line_offsets(cols: u8, rows: u8, line_offsets: [u8; 4]) -> DisplaySize {
+        DisplaySize {
+            cols,
+            rows,
+            line_offsets,
+        }
+    }
+
+    /// Whether there are at most 4 rows, and every row fits in one DDRAM
+    /// line. More than one row means the display runs in two line mode,
+    /// with lines at 0x00-0x27 and 0x40-0x67, and one row in one line mode,
+    /// with a single line at 0x00-0x4F.
+    pub(crate) fn fits_ddram(&self) -> bool {
+        let lines: &[(u8, u8)] = if self.rows > 1 {
+            &TWO_LINE_DDRAM
+        } else {
+            &ONE_LINE_DDRAM
+        };
+
+        self.rows as usize <= self.line_offsets.len()
+            && self.line_offsets[..self.rows as usize]
+                .iter()
+                .all(|&offset| {
+                    lines.iter().any(|&(start, end)| {
+                        offset >= start
+                            && match offset.checked_add(self.cols) {
+                                Some(row_end) => row_end <= end,
+                                None => false,
+                            }
+                    })
+                })
+    }
+}
+
+impl Default for DisplaySize {
+    fn default() -> DisplaySize {
+        DisplaySize::new(16, 2)
+    }
+}
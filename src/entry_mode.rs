@@ -1,20 +1,16 @@
 /// Determines if the cursor should be incremented or decremented on write
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CursorMode {
+    #[default]
     Increment,
     Decrement,
 }
 
-impl Default for CursorMode {
-    fn default() -> CursorMode {
-        CursorMode::Increment
-    }
-}
-
 /// Determines if the screen should be shifted on write
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ShiftMode {
     Enabled,
+    #[default]
     Disabled,
 }
 
@@ -28,19 +24,22 @@ impl From<bool> for ShiftMode {
     }
 }
 
-impl Default for ShiftMode {
-    fn default() -> ShiftMode {
-        ShiftMode::Disabled
-    }
-}
-
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct EntryMode {
     pub cursor_mode: CursorMode,
     pub shift_mode: ShiftMode,
 }
 
 impl EntryMode {
+    /// Build an entry mode from which way the cursor moves and whether the
+    /// display shifts with it
+    pub fn new(cursor_mode: CursorMode, shift_mode: ShiftMode) -> EntryMode {
+        EntryMode {
+            cursor_mode,
+            shift_mode,
+        }
+    }
+
     pub fn as_byte(&self) -> u8 {
         let cursor_bits = match self.cursor_mode {
             CursorMode::Increment => 0b0000_0010,
@@ -94,4 +93,11 @@ mod tests {
 
         assert!(em.as_byte() & 0b0000_0001 == 0);
     }
+
+    #[test]
+    fn new() {
+        let em = EntryMode::new(CursorMode::Decrement, ShiftMode::Enabled);
+
+        assert_eq!(em.as_byte(), 0b0000_0101);
+    }
 }
@@ -1,3 +1,31 @@
-#[derive(Debug)]
-pub struct Error;
+use embedded_hal::digital::ErrorKind;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    /// Talking to a peripheral on the bus failed
+    Bus,
+    /// Driving or reading a pin failed, with the kind of error the pin
+    /// reported
+    Pin(ErrorKind),
+    /// The bus has no way of reading back from the display
+    ReadUnsupported,
+    /// The display stayed busy for longer than any instruction should take
+    BusyTimeout,
+    /// No display answered on the bus
+    NoDevice,
+    /// A position outside the display, or a custom character slot past the
+    /// last, was asked for
+    OutOfBounds,
+    /// The display can't do what was asked in the way it is set up, such as
+    /// using the 5x10 font on two lines
+    Unsupported,
+}
+
+impl Error {
+    /// Keep the kind of a pin's error
+    pub(crate) fn pin<E: embedded_hal::digital::Error>(error: E) -> Self {
+        Error::Pin(error.kind())
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
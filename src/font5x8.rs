@@ -0,0 +1,241 @@
+//! The 5x8 pixel font used to draw text into CGRAM glyphs.
+//!
+//! Glyphs are returned in the layout the controller expects when custom
+//! characters are written to CGRAM: eight rows from top to bottom, each holding five pixels in its lower
+//! bits with the leftmost pixel in bit 4. The bottom row is left blank for
+//! the cursor, as in the controller's own font.
+//!
+//! ```rust,ignore
+//! let a = font5x8::glyph('A').unwrap();
+//! let b = font5x8::glyph('B').unwrap();
+//!
+//! // The cell straddling 'A' and 'B', two pixels into 'A'
+//! let between = font5x8::combine(a, b, 2);
+//! ```
+
+/// Width of a glyph in pixels
+pub const WIDTH: u8 = 5;
+
+/// Height of a glyph in pixels
+pub const HEIGHT: u8 = 8;
+
+/// Printable ASCII from `' '` to `'~'`, one byte per column from left to
+/// right with the top pixel in bit 0
+const ASCII: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Symbols outside ASCII, in the same column layout as `ASCII`
+const SYMBOLS: [(char, [u8; 5]); 1] = [('°', [0x06, 0x09, 0x09, 0x06, 0x00])];
+
+/// Look up the glyph for `c`, covering printable ASCII and the degree sign
+pub fn glyph(c: char) -> Option<[u8; 8]> {
+    let columns = match c {
+        ' '..='~' => &ASCII[c as usize - ' ' as usize],
+        _ => &SYMBOLS.iter().find(|&&(symbol, _)| symbol == c)?.1,
+    };
+
+    let mut rows = [0u8; 8];
+
+    for (x, column) in columns.iter().enumerate() {
+        for (y, row) in rows.iter_mut().enumerate() {
+            if column & (1 << y) != 0 {
+                *row |= 0b1_0000 >> x;
+            }
+        }
+    }
+
+    Some(rows)
+}
+
+/// Move every pixel of `glyph` left by `pixels`, dropping the ones that fall
+/// off the edge
+pub fn shift_left(glyph: [u8; 8], pixels: u8) -> [u8; 8] {
+    if pixels >= WIDTH {
+        return [0; 8];
+    }
+
+    glyph.map(|row| (row << pixels) & 0b1_1111)
+}
+
+/// Move every pixel of `glyph` right by `pixels`, dropping the ones that fall
+/// off the edge
+pub fn shift_right(glyph: [u8; 8], pixels: u8) -> [u8; 8] {
+    if pixels >= WIDTH {
+        return [0; 8];
+    }
+
+    glyph.map(|row| (row & 0b1_1111) >> pixels)
+}
+
+/// The cell seen when `left` and `right` are placed side by side and the view
+/// is moved `offset` pixels (up to 5) from `left` towards `right`.
+///
+/// An offset of 0 is `left` and an offset of 5 is `right`; anything in between
+/// is the frame of a smooth scroll from one to the other.
+pub fn combine(left: [u8; 8], right: [u8; 8], offset: u8) -> [u8; 8] {
+    let offset = offset.min(WIDTH);
+    let mut rows = [0u8; 8];
+
+    for (row, (l, r)) in rows.iter_mut().zip(left.iter().zip(right.iter())) {
+        let strip = (((*l & 0b1_1111) as u16) << WIDTH) | (*r & 0b1_1111) as u16;
+        *row = ((strip >> (WIDTH - offset)) & 0b1_1111) as u8;
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: [u8; 8] = [
+        0b01110, 0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b00000,
+    ];
+
+    const ZERO: [u8; 8] = [
+        0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110, 0b00000,
+    ];
+
+    const DEGREE: [u8; 8] = [
+        0b01100, 0b10010, 0b10010, 0b01100, 0b00000, 0b00000, 0b00000, 0b00000,
+    ];
+
+    #[test]
+    fn reference_glyphs() {
+        assert_eq!(glyph('A'), Some(A));
+        assert_eq!(glyph('0'), Some(ZERO));
+        assert_eq!(glyph('°'), Some(DEGREE));
+        assert_eq!(glyph(' '), Some([0; 8]));
+    }
+
+    #[test]
+    fn covers_printable_ascii() {
+        for c in ' '..='~' {
+            let glyph = glyph(c).unwrap();
+
+            assert_eq!(glyph[7], 0, "{:?} draws on the cursor row", c);
+            assert!(glyph.iter().all(|&row| row < 0b10_0000));
+            assert!(c == ' ' || glyph != [0; 8], "{:?} is blank", c);
+        }
+    }
+
+    #[test]
+    fn unknown_characters() {
+        assert_eq!(glyph('\n'), None);
+        assert_eq!(glyph('€'), None);
+    }
+
+    #[test]
+    fn shifting() {
+        assert_eq!(shift_left(A, 1)[4], 0b11110);
+        assert_eq!(shift_right(A, 1)[4], 0b01111);
+        assert_eq!(shift_left(A, 5), [0; 8]);
+        assert_eq!(shift_right(A, 0), A);
+    }
+
+    #[test]
+    fn combining() {
+        assert_eq!(combine(A, ZERO, 0), A);
+        assert_eq!(combine(A, ZERO, 5), ZERO);
+        assert_eq!(combine(A, ZERO, 9), ZERO);
+
+        // Two pixels into 'A': its right three columns, then the left two of '0'
+        assert_eq!(combine(A, ZERO, 2)[2], 0b00110);
+        assert_eq!(combine(A, ZERO, 2)[4], 0b11111);
+    }
+}
@@ -0,0 +1,54 @@
+/// The number of lines the controller drives, chosen along with the bus
+/// width and font by the function set instruction. Four row displays run as
+/// two lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lines {
+    One,
+    Two,
+}
+
+/// The character font, chosen along with the bus width and number of lines
+/// by the function set instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontMode {
+    /// 5x8 dots, with 8 custom characters of 8 rows
+    #[default]
+    FiveByEight,
+    /// 5x10 dots, with 4 custom characters of 11 rows. Only one line
+    /// displays can use it.
+    FiveByTen,
+}
+
+impl FontMode {
+    /// The font bit of the function set instruction
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            FontMode::FiveByEight => 0,
+            FontMode::FiveByTen => 0b0000_0100,
+        }
+    }
+
+    /// How many custom characters fit in CGRAM
+    pub(crate) fn slots(self) -> u8 {
+        match self {
+            FontMode::FiveByEight => 8,
+            FontMode::FiveByTen => 4,
+        }
+    }
+
+    /// How many rows a custom character has
+    pub(crate) fn rows(self) -> usize {
+        match self {
+            FontMode::FiveByEight => 8,
+            FontMode::FiveByTen => 11,
+        }
+    }
+
+    /// How far apart custom characters are in CGRAM
+    pub(crate) fn stride(self) -> u8 {
+        match self {
+            FontMode::FiveByEight => 8,
+            FontMode::FiveByTen => 16,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Custom characters drawn as rows of pixels, and a few ready-made ones.
+//!
+//! Each row is five characters, `'#'` or `'X'` for a lit pixel and `'.'` or
+//! `' '` for an unlit one. The [glyph!](../macro.glyph.html) macro turns
+//! eight of them into the pattern
+//! [create_char](../struct.HD44780.html#method.create_char) takes, while
+//! compiling, so a row of the wrong width fails the build.
+//!
+//! ```rust,ignore
+//! const SMILEY: [u8; 8] = glyph![
+//!     ".....",
+//!     ".#.#.",
+//!     ".#.#.",
+//!     ".....",
+//!     "#...#",
+//!     ".###.",
+//!     ".....",
+//!     ".....",
+//! ];
+//! lcd.create_char(0, SMILEY, &mut delay)?;
+//! lcd.create_char(1, glyphs::DEGREE, &mut delay)?;
+//! ```
+
+/// Turn eight rows of five pixels into a custom character pattern, with the
+/// leftmost pixel in bit 4.
+///
+/// Panics if a row isn't five characters of `'#'`, `'X'`, `'.'` or `' '`,
+/// which is a compile error when used in a constant or through
+/// [glyph!](../macro.glyph.html).
+pub const fn glyph_from_rows(rows: [&str; 8]) -> [u8; 8] {
+    let mut pattern = [0u8; 8];
+
+    let mut row = 0;
+    while row < 8 {
+        let pixels = rows[row].as_bytes();
+        if pixels.len() != 5 {
+            panic!("each glyph row must be exactly 5 pixels wide");
+        }
+
+        let mut col = 0;
+        while col < 5 {
+            let lit = match pixels[col] {
+                b'#' | b'X' => 1,
+                b'.' | b' ' => 0,
+                _ => panic!("glyph pixels must be '#' or 'X' for lit, '.' or ' ' for unlit"),
+            };
+            pattern[row] |= lit << (4 - col);
+            col += 1;
+        }
+
+        row += 1;
+    }
+
+    pattern
+}
+
+/// Build a custom character pattern from eight rows of five pixels while
+/// compiling, see [glyphs](glyphs/index.html)
+#[macro_export]
+macro_rules! glyph {
+    ($($row:expr),+ $(,)?) => {{
+        const GLYPH: [u8; 8] = $crate::glyphs::glyph_from_rows([$($row),+]);
+        GLYPH
+    }};
+}
+
+/// A degree sign, sitting high in the cell
+#[rustfmt::skip]
+pub const DEGREE: [u8; 8] = glyph![
+    ".##..",
+    "#..#.",
+    "#..#.",
+    ".##..",
+    ".....",
+    ".....",
+    ".....",
+    ".....",
+];
+
+/// A bell
+#[rustfmt::skip]
+pub const BELL: [u8; 8] = glyph![
+    "..#..",
+    ".###.",
+    ".###.",
+    ".###.",
+    "#####",
+    ".....",
+    "..#..",
+    ".....",
+];
+
+/// An arrow pointing up
+#[rustfmt::skip]
+pub const ARROW_UP: [u8; 8] = glyph![
+    "..#..",
+    ".###.",
+    "#.#.#",
+    "..#..",
+    "..#..",
+    "..#..",
+    "..#..",
+    ".....",
+];
+
+/// An arrow pointing down
+#[rustfmt::skip]
+pub const ARROW_DOWN: [u8; 8] = glyph![
+    "..#..",
+    "..#..",
+    "..#..",
+    "..#..",
+    "#.#.#",
+    ".###.",
+    "..#..",
+    ".....",
+];
+
+/// The outline of an empty battery
+#[rustfmt::skip]
+pub const BATTERY: [u8; 8] = glyph![
+    ".##..",
+    "####.",
+    "#..#.",
+    "#..#.",
+    "#..#.",
+    "#..#.",
+    "####.",
+    ".....",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_pack_with_the_leftmost_pixel_highest() {
+        #[rustfmt::skip]
+        let pattern = glyph![
+            "#....",
+            "....#",
+            "#####",
+            ".....",
+            "X X X",
+            " X X ",
+            "#.#.#",
+            ".#.#.",
+        ];
+
+        assert_eq!(pattern, [0x10, 0x01, 0x1F, 0x00, 0x15, 0x0A, 0x15, 0x0A]);
+    }
+
+    #[test]
+    fn ready_made_glyphs_match_their_drawings() {
+        assert_eq!(DEGREE, [0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(BELL, [0x04, 0x0E, 0x0E, 0x0E, 0x1F, 0x00, 0x04, 0x00]);
+        assert_eq!(ARROW_UP, [0x04, 0x0E, 0x15, 0x04, 0x04, 0x04, 0x04, 0x00]);
+        assert_eq!(ARROW_DOWN, [0x04, 0x04, 0x04, 0x04, 0x15, 0x0E, 0x04, 0x00]);
+        assert_eq!(BATTERY, [0x0C, 0x1E, 0x12, 0x12, 0x12, 0x12, 0x1E, 0x00]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn short_rows_are_rejected() {
+        glyph_from_rows([
+            "####", "#####", "#####", "#####", "#####", "#####", "#####", "#####",
+        ]);
+    }
+}
@@ -1,17 +1,31 @@
 #![no_std]
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
 //use core::fmt::Result;
 //use core::fmt::Write;
 
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
-use embedded_hal::blocking::i2c;
-use embedded_hal::digital::v2::OutputPin;
+use core::cell::RefCell;
+use core::time::Duration;
+
+use crate::delay::DelayNs;
+use crate::digital::{InputPin, OutputPin};
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_0_2::blocking::i2c;
+use embedded_hal_0_2::blocking::spi;
 
 pub mod bus;
-use bus::{DataBus, EightBitBus, FourBitBus, I2CBus};
+use bus::{
+    ActiveController, Backlight, BacklightBus, BackpackPinout, Brightness, BusHooks, DataBus,
+    DimmerBus, DualEnableBus, EightBitBus, FourBitBus, GroveRgbBus, I2CBus, LineCharacteristics,
+    Mcp23008Bus, Mcp23017Bus, NibbleBus, OutputPort, PinMap, Port, PortBus, RgbBacklight,
+    SerialBus164, SharedFourBitBus, ShiftRegisterBus, ShiftRegisterPinout, St7032Bus, St7036Bus,
+};
+
+pub mod delay;
+pub mod digital;
 
 pub mod error;
-use error::Result;
+use error::{Error, Result};
 
 pub mod entry_mode;
 
@@ -21,34 +35,129 @@ pub mod display_mode;
 
 pub use display_mode::DisplayMode;
 
+pub mod display_size;
+
+pub use display_size::DisplaySize;
+
+pub mod function_set;
+
+pub use function_set::{FontMode, Lines};
+
+pub mod sized;
+
+pub use sized::SizedHd44780;
+
+pub mod font5x8;
+
+pub mod glyphs;
+
+pub mod timing;
+use timing::TimingConfig;
+
+pub mod controller;
+use controller::ControllerVariant;
+
+pub mod config;
+pub use config::Config;
+
+#[cfg(test)]
+mod mock;
+
 pub struct HD44780<B: DataBus> {
     bus: B,
     entry_mode: EntryMode,
     display_mode: DisplayMode,
+    pacing: Option<u32>,
+    paced_bytes: u32,
+    wait_strategy: WaitStrategy,
+    timing: TimingConfig,
+    variant: ControllerVariant,
+    init: Init,
+    size: DisplaySize,
+    font: FontMode,
 }
 
 /// Used in the direction argument for shifting the cursor and the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
 }
 
 /// Used in set_display_mode to make the parameters more clear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Display {
     On,
     Off,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Cursor {
     Visible,
     Invisible,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CursorBlink {
     On,
     Off,
 }
 
+/// How to wait for the display to finish each instruction, used in
+/// set_wait_strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStrategy {
+    /// Read the busy flag until the display reports it is ready, which needs
+    /// a bus that can read. Buses that can't fall back to a fixed delay.
+    BusyFlag,
+    /// Sleep for a fixed time long enough for most instructions
+    FixedDelay,
+}
+
+/// The set up the display was constructed with, so that
+/// [reinit](struct.HD44780.html#method.reinit) can run it again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Init {
+    FourBit,
+    EightBit,
+    /// WS0010 OLED, with whether it has an 8-bit interface
+    Ws0010(bool),
+    St7032,
+    /// ST7036, with whether it has an 8-bit interface
+    St7036(bool),
+    GroveRgb,
+}
+
+impl Init {
+    /// Whether the display was set up with an 8-bit interface
+    fn eight_bit(self) -> bool {
+        match self {
+            Init::FourBit | Init::GroveRgb => false,
+            Init::EightBit | Init::St7032 => true,
+            Init::Ws0010(eight_bit) | Init::St7036(eight_bit) => eight_bit,
+        }
+    }
+}
+
+/// The function set instruction for the bus width, with one line for
+/// `rows` of 1 and two otherwise, and `font`
+fn function_set_for(eight_bit: bool, rows: u8, font: FontMode) -> u8 {
+    let width = if eight_bit { 0b0001_0000 } else { 0 };
+    let lines = if rows > 1 { 0b0000_1000 } else { 0 };
+
+    0b0010_0000 | width | lines | font.bits()
+}
+
+/// How many times to read the busy flag before giving up on the display
+const BUSY_POLLS: u16 = 1000;
+
+/// How long to back off between reads of the busy flag
+const BUSY_BACKOFF_US: u16 = 10;
+
+/// The DDRAM cell [probe](struct.HD44780.html#method.probe) writes to, the
+/// last one on the first line, which only 40 column displays show
+const PROBE_ADDRESS: u8 = 0x27;
+
 impl<
         RS: OutputPin,
         EN: OutputPin,
@@ -65,15 +174,15 @@ impl<
     /// Create an instance of a `HD44780` from 8 data pins, a register select
     /// pin, an enable pin and a struct implementing the delay trait.
     /// - The delay instance is used to sleep between commands to
-    /// ensure the `HD44780` has enough time to process commands.
+    ///   ensure the `HD44780` has enough time to process commands.
     /// - The eight db0..db7 pins are used to send and recieve with
-    ///  the `HD44780`.
+    ///   the `HD44780`.
     /// - The register select pin is used to tell the `HD44780`
-    /// if incoming data is a command or data.
+    ///   if incoming data is a command or data.
     /// - The enable pin is used to tell the `HD44780` that there
-    /// is data on the 8 data pins and that it should read them in.
+    ///   is data on the 8 data pins and that it should read them in.
     ///
-    pub fn new_8bit<D: DelayUs<u16> + DelayMs<u8>>(
+    pub fn new_8bit<D: DelayNs>(
         rs: RS,
         en: EN,
         d0: D0,
@@ -86,474 +195,4101 @@ impl<
         d7: D7,
         delay: &mut D,
     ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
-        let mut hd = HD44780 {
-            bus: EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
+        let mut hd = HD44780::from_parts(
+            EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
+            Init::EightBit,
+            Config::default(),
+        )?;
 
         hd.init_8bit(delay)?;
 
-        return Ok(hd);
+        Ok(hd)
     }
-}
 
-impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
-    HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>
-{
-    /// Create an instance of a `HD44780` from 4 data pins, a register select
-    /// pin, an enable pin and a struct implementing the delay trait.
-    /// - The delay instance is used to sleep between commands to
-    /// ensure the `HD44780` has enough time to process commands.
-    /// - The four db0..db3 pins are used to send and recieve with
-    ///  the `HD44780`.
-    /// - The register select pin is used to tell the `HD44780`
-    /// if incoming data is a command or data.
-    /// - The enable pin is used to tell the `HD44780` that there
-    /// is data on the 4 data pins and that it should read them in.
-    ///
-    /// This mode operates differently than 8 bit mode by using 4 less
-    /// pins for data, which is nice on devices with less I/O although
-    /// the I/O takes a 'bit' longer
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), set up as `config` says, see
+    /// [Config](config/struct.Config.html).
     ///
-    /// Instead of commands being sent byte by byte each command is
-    /// broken up into it's upper and lower nibbles (4 bits) before
-    /// being sent over the data bus
+    /// The 5x10 font only works on one line, so asking for it on a display
+    /// with more than one row returns `Error::Unsupported`.
     ///
-    pub fn new_4bit<D: DelayUs<u16> + DelayMs<u8>>(
+    pub fn new_8bit_with_config<D: DelayNs>(
         rs: RS,
         en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
         d4: D4,
         d5: D5,
         d6: D6,
         d7: D7,
+        config: Config,
         delay: &mut D,
-    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
-        let mut hd = HD44780 {
-            bus: FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
+            Init::EightBit,
+            config,
+        )?;
 
-        hd.init_4bit(delay)?;
+        hd.init_8bit(delay)?;
 
-        return Ok(hd);
+        Ok(hd)
     }
-}
 
-impl<I2C: i2c::Write> HD44780<I2CBus<I2C>> {
-    /// Create an instance of a `HD44780` from an i2c write peripheral,
-    /// the `HD44780` I2C address and a struct implementing the delay trait.
-    /// - The delay instance is used to sleep between commands to
-    /// ensure the `HD44780` has enough time to process commands.
-    /// - The i2c peripheral is used to send data to the `HD44780` and to set
-    /// its register select and enable pins.
-    ///
-    /// This mode operates on an I2C bus, using an I2C to parallel port expander
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), waiting on the display as set out in
+    /// `timing` from power up on, see
+    /// [TimingConfig](timing/struct.TimingConfig.html).
     ///
-    pub fn new_i2c<D: DelayUs<u16> + DelayMs<u8>>(
-        i2c_bus: I2C,
-        address: u8,
+    pub fn new_8bit_with_timing<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        timing: TimingConfig,
         delay: &mut D,
-    ) -> Result<HD44780<I2CBus<I2C>>> {
-        let mut hd = HD44780 {
-            bus: I2CBus::new(i2c_bus, address),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
-
-        hd.init_4bit(delay)?;
-
-        return Ok(hd);
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        HD44780::new_8bit_with_config(
+            rs,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                timing,
+                ..Config::default()
+            },
+            delay,
+        )
     }
-}
 
-impl<B> HD44780<B>
-where
-    B: DataBus,
-{
-    /// Unshifts the display and sets the cursor position to 0
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), set up and timed for a particular
+    /// controller, see
+    /// [ControllerVariant](controller/enum.ControllerVariant.html).
     ///
-    /// ```rust,ignore
-    /// lcd.reset();
-    /// ```
-    pub fn reset<D: DelayUs<u16> + DelayMs<u8>>(&mut self, delay: &mut D) -> Result<()> {
-        self.write_command(0b0000_0010, delay)?;
-
-        Ok(())
+    pub fn new_8bit_with_variant<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        variant: ControllerVariant,
+        delay: &mut D,
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        HD44780::new_8bit_with_config(
+            rs,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config::for_variant(variant),
+            delay,
+        )
     }
 
-    /// Set if the display should be on, if the cursor should be
-    /// visible, and if the cursor should blink
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), for a display of `size` rather than
+    /// 16x2.
     ///
-    /// Note: This is equivilent to calling all of the other relavent
-    /// methods however this operation does it all in one go to the `HD44780`
-    pub fn set_display_mode<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        display_mode: DisplayMode,
+    pub fn new_8bit_with_size<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        size: DisplaySize,
         delay: &mut D,
-    ) -> Result<()> {
-        self.display_mode = display_mode;
-
-        let cmd_byte = self.display_mode.as_byte();
-
-        self.write_command(cmd_byte, delay)?;
-
-        Ok(())
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        HD44780::new_8bit_with_config(
+            rs,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                size,
+                ..Config::default()
+            },
+            delay,
+        )
     }
 
-    /// Clear the entire display
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit_with_size](#method.new_8bit_with_size), using `font`.
     ///
-    /// ```rust,ignore
-    /// lcd.clear();
-    /// ```
-    pub fn clear<D: DelayUs<u16> + DelayMs<u8>>(&mut self, delay: &mut D) -> Result<()> {
-        self.write_command(0b0000_0001, delay)?;
-
-        Ok(())
+    /// The 5x10 font only works on one line, so asking for it on a display
+    /// with more than one row returns `Error::Unsupported`.
+    ///
+    pub fn new_8bit_with_font<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        size: DisplaySize,
+        font: FontMode,
+        delay: &mut D,
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        HD44780::new_8bit_with_config(
+            rs,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                size,
+                font,
+                ..Config::default()
+            },
+            delay,
+        )
     }
 
-    /// If enabled, automatically scroll the display when a new
-    /// character is written to the display
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), for a Winstar WS0010 (or RS0010) OLED
+    /// character display.
     ///
-    /// ```rust,ignore
-    /// lcd.set_autoscroll(true);
-    /// ```
-    pub fn set_autoscroll<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        enabled: bool,
+    /// The WS0010 takes the usual instructions once it is running, but needs
+    /// its internal power turned on during set up, so this runs its own
+    /// initialization rather than the usual 8-bit one.
+    ///
+    pub fn new_8bit_ws0010<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
         delay: &mut D,
-    ) -> Result<()> {
-        self.entry_mode.shift_mode = enabled.into();
-
-        let cmd = self.entry_mode.as_byte();
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
+            Init::Ws0010(true),
+            Config::for_variant(ControllerVariant::Ws0010),
+        )?;
 
-        self.write_command(cmd, delay)?;
+        hd.init_ws0010(delay)?;
 
-        Ok(())
+        Ok(hd)
     }
+}
 
-    /// Set if the cursor should be visible
-    pub fn set_cursor_visibility<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        visibility: Cursor,
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        BL: OutputPin,
+    > HD44780<BacklightBus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>, BL>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), with the backlight switched by its own
+    /// pin.
+    /// - The backlight pin is driven high to turn the backlight on, which
+    ///   it is once the display is set up.
+    ///
+    pub fn new_8bit_with_backlight<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        bl: BL,
         delay: &mut D,
-    ) -> Result<()> {
-        self.display_mode.cursor_visibility = visibility;
+    ) -> Result<HD44780<BacklightBus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>, BL>>>
+    {
+        let mut hd = HD44780::from_parts(
+            BacklightBus::from_bus(
+                EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
+                bl,
+            ),
+            Init::EightBit,
+            Config::default(),
+        )?;
 
-        let cmd = self.display_mode.as_byte();
-
-        self.write_command(cmd, delay)?;
+        hd.init_8bit(delay)?;
+        hd.set_backlight(true)?;
 
-        Ok(())
+        Ok(hd)
     }
+}
 
-    /// Set if the characters on the display should be visible
-    pub fn set_display<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        display: Display,
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin + InputPin,
+        D1: OutputPin + InputPin,
+        D2: OutputPin + InputPin,
+        D3: OutputPin + InputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>>
+{
+    /// Create an instance of a `HD44780` that can read back from the
+    /// display, from 8 data pins, a register select pin, a read/write pin,
+    /// an enable pin and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The eight db0..db7 pins are used to send and recieve with
+    ///   the `HD44780`, and must be readable while set high, such as
+    ///   open drain outputs with pull-ups.
+    /// - The register select pin is used to tell the `HD44780`
+    ///   if incoming data is a command or data.
+    /// - The read/write pin is used to tell the `HD44780` whether it
+    ///   should read from or drive the data pins.
+    /// - The enable pin is used to tell the `HD44780` that there
+    ///   is data on the 8 data pins and that it should read them in.
+    ///
+    pub fn new_8bit_rw<D: DelayNs>(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
         delay: &mut D,
-    ) -> Result<()> {
-        self.display_mode.display = display;
+    ) -> Result<HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>>> {
+        let mut hd = HD44780::from_parts(
+            EightBitBus::from_pins_rw(rs, rw, en, d0, d1, d2, d3, d4, d5, d6, d7)?,
+            Init::EightBit,
+            Config::default(),
+        )?;
 
-        let cmd = self.display_mode.as_byte();
+        hd.init_8bit(delay)?;
 
-        self.write_command(cmd, delay)?;
+        // The busy flag can only be trusted once the display is initialized
+        hd.wait_strategy = WaitStrategy::BusyFlag;
 
-        Ok(())
+        Ok(hd)
     }
+}
 
-    /// Set if the cursor should blink
-    pub fn set_cursor_blink<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        blink: CursorBlink,
-        delay: &mut D,
-    ) -> Result<()> {
-        self.display_mode.cursor_blink = blink;
-
-        let cmd = self.display_mode.as_byte();
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        RW,
+    > HD44780<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>>
+where
+    EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7, RW>: DataBus,
+{
+    /// Set the functions called at each step of a transfer on the bus, see
+    /// [BusHooks](bus/struct.BusHooks.html)
+    pub fn set_bus_hooks(&mut self, hooks: BusHooks) {
+        self.bus.set_hooks(hooks);
+    }
 
-        self.write_command(cmd, delay)?;
+    /// Set how slowly the lines to the display settle so the bus timing can
+    /// allow for it, see [LineCharacteristics](bus/struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.bus.set_line_characteristics(line);
+    }
+
+    /// How long writing each byte takes with the current timing and line
+    /// characteristics, from setting the data lines through the wait for
+    /// the display when the busy flag isn't read. Shows what slow lines
+    /// cost.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_line_characteristics(LineCharacteristics {
+    ///     rise_time_us: 5,
+    ///     fall_time_us: 1,
+    /// });
+    /// let per_byte = lcd.byte_time();
+    /// ```
+    pub fn byte_time(&self) -> Duration {
+        let us = self.bus.byte_transfer_us() + u32::from(self.timing.command_us);
+        Duration::from_micros(u64::from(us))
+    }
+}
+
+impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
+    HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `HD44780` from 4 data pins, a register select
+    /// pin, an enable pin and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The four db0..db3 pins are used to send and recieve with
+    ///   the `HD44780`.
+    /// - The register select pin is used to tell the `HD44780`
+    ///   if incoming data is a command or data.
+    /// - The enable pin is used to tell the `HD44780` that there
+    ///   is data on the 4 data pins and that it should read them in.
+    ///
+    /// This mode operates differently than 8 bit mode by using 4 less
+    /// pins for data, which is nice on devices with less I/O although
+    /// the I/O takes a 'bit' longer
+    ///
+    /// Instead of commands being sent byte by byte each command is
+    /// broken up into it's upper and lower nibbles (4 bits) before
+    /// being sent over the data bus
+    ///
+    pub fn new_4bit<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), for boards where the four data pins
+    /// aren't wired to DB4 to DB7 in order, see [PinMap](bus/enum.PinMap.html).
+    ///
+    pub fn new_4bit_with_map<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        map: PinMap,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            FourBitBus::from_pins_with_map(rs, en, d4, d5, d6, d7, map),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), set up as `config` says, see
+    /// [Config](config/struct.Config.html).
+    ///
+    /// The 5x10 font only works on one line, so asking for it on a display
+    /// with more than one row returns `Error::Unsupported`.
+    ///
+    pub fn new_4bit_with_config<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        config: Config,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
+            Init::FourBit,
+            config,
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), waiting on the display as set out in
+    /// `timing` from power up on, see
+    /// [TimingConfig](timing/struct.TimingConfig.html).
+    ///
+    pub fn new_4bit_with_timing<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        timing: TimingConfig,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        HD44780::new_4bit_with_config(
+            rs,
+            en,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                timing,
+                ..Config::default()
+            },
+            delay,
+        )
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), set up and timed for a particular
+    /// controller, see
+    /// [ControllerVariant](controller/enum.ControllerVariant.html).
+    ///
+    pub fn new_4bit_with_variant<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        variant: ControllerVariant,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        HD44780::new_4bit_with_config(rs, en, d4, d5, d6, d7, Config::for_variant(variant), delay)
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), for a display of `size` rather than
+    /// 16x2.
+    ///
+    pub fn new_4bit_with_size<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        size: DisplaySize,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        HD44780::new_4bit_with_config(
+            rs,
+            en,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                size,
+                ..Config::default()
+            },
+            delay,
+        )
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit_with_size](#method.new_4bit_with_size), using `font`.
+    ///
+    /// The 5x10 font only works on one line, so asking for it on a display
+    /// with more than one row returns `Error::Unsupported`.
+    ///
+    pub fn new_4bit_with_font<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        size: DisplaySize,
+        font: FontMode,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        HD44780::new_4bit_with_config(
+            rs,
+            en,
+            d4,
+            d5,
+            d6,
+            d7,
+            Config {
+                size,
+                font,
+                ..Config::default()
+            },
+            delay,
+        )
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), for a Winstar WS0010 (or RS0010) OLED
+    /// character display.
+    ///
+    /// The WS0010 takes the usual instructions once it is running, but needs
+    /// its internal power turned on during set up, so this runs its own
+    /// initialization rather than the usual 4-bit one.
+    ///
+    pub fn new_4bit_ws0010<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
+            Init::Ws0010(false),
+            Config::for_variant(ControllerVariant::Ws0010),
+        )?;
+
+        hd.sync_ws0010_4bit(delay)?;
+        hd.init_ws0010(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        BL: OutputPin,
+    > HD44780<BacklightBus<FourBitBus<RS, EN, D4, D5, D6, D7>, BL>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), with the backlight switched by its own
+    /// pin.
+    /// - The backlight pin is driven high to turn the backlight on, which
+    ///   it is once the display is set up.
+    ///
+    pub fn new_4bit_with_backlight<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        bl: BL,
+        delay: &mut D,
+    ) -> Result<HD44780<BacklightBus<FourBitBus<RS, EN, D4, D5, D6, D7>, BL>>> {
+        let mut hd = HD44780::from_parts(
+            BacklightBus::from_bus(FourBitBus::from_pins(rs, en, d4, d5, d6, d7), bl),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+        hd.set_backlight(true)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin + InputPin,
+        D5: OutputPin + InputPin,
+        D6: OutputPin + InputPin,
+        D7: OutputPin + InputPin,
+        RW: OutputPin,
+    > HD44780<FourBitBus<RS, EN, D4, D5, D6, D7, RW>>
+{
+    /// Create an instance of a `HD44780` that can read back from the
+    /// display, from 4 data pins, a register select pin, a read/write pin,
+    /// an enable pin and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The four db0..db3 pins are used to send and recieve with
+    ///   the `HD44780`, and must be readable while set high, such as
+    ///   open drain outputs with pull-ups.
+    /// - The register select pin is used to tell the `HD44780`
+    ///   if incoming data is a command or data.
+    /// - The read/write pin is used to tell the `HD44780` whether it
+    ///   should read from or drive the data pins.
+    /// - The enable pin is used to tell the `HD44780` that there
+    ///   is data on the 4 data pins and that it should read them in.
+    ///
+    pub fn new_4bit_rw<D: DelayNs>(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<FourBitBus<RS, EN, D4, D5, D6, D7, RW>>> {
+        let mut hd = HD44780::from_parts(
+            FourBitBus::from_pins_rw(rs, rw, en, d4, d5, d6, d7)?,
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        // The busy flag can only be trusted once the display is initialized
+        hd.wait_strategy = WaitStrategy::BusyFlag;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        RW,
+    > HD44780<FourBitBus<RS, EN, D4, D5, D6, D7, RW>>
+where
+    FourBitBus<RS, EN, D4, D5, D6, D7, RW>: DataBus,
+{
+    /// Set the functions called at each step of a transfer on the bus, see
+    /// [BusHooks](bus/struct.BusHooks.html)
+    pub fn set_bus_hooks(&mut self, hooks: BusHooks) {
+        self.bus.set_hooks(hooks);
+    }
+
+    /// Set how slowly the lines to the display settle so the bus timing can
+    /// allow for it, see [LineCharacteristics](bus/struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.bus.set_line_characteristics(line);
+    }
+
+    /// How long writing each byte takes with the current timing and line
+    /// characteristics, from setting the data lines through the wait for
+    /// the display when the busy flag isn't read. Shows what slow lines
+    /// cost.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_line_characteristics(LineCharacteristics {
+    ///     rise_time_us: 5,
+    ///     fall_time_us: 1,
+    /// });
+    /// let per_byte = lcd.byte_time();
+    /// ```
+    pub fn byte_time(&self) -> Duration {
+        let us = self.bus.byte_transfer_us() + u32::from(self.timing.command_us);
+        Duration::from_micros(u64::from(us))
+    }
+}
+
+impl<RS: OutputPin, EN: OutputPin, P: OutputPort> HD44780<PortBus<RS, EN, P>> {
+    /// Create an instance of a `HD44780` from an 8-bit output port, a
+    /// register select pin, an enable pin and a struct implementing the
+    /// delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The port is used to send to the `HD44780`, a whole byte at a time,
+    ///   with bit 0 on db0.
+    /// - The register select pin is used to tell the `HD44780`
+    ///   if incoming data is a command or data.
+    /// - The enable pin is used to tell the `HD44780` that there
+    ///   is data on the port and that it should read it in.
+    ///
+    pub fn new_port<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        port: P,
+        delay: &mut D,
+    ) -> Result<HD44780<PortBus<RS, EN, P>>> {
+        let mut hd = HD44780::from_parts(
+            PortBus::from_port(rs, en, port),
+            Init::EightBit,
+            Config::default(),
+        )?;
+
+        hd.init_8bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Set how slowly the lines to the display settle so the bus timing can
+    /// allow for it, see [LineCharacteristics](bus/struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.bus.set_line_characteristics(line);
+    }
+
+    /// How long writing each byte takes with the current timing and line
+    /// characteristics, from setting the data lines through the wait for
+    /// the display when the busy flag isn't read
+    pub fn byte_time(&self) -> Duration {
+        let us = self.bus.byte_transfer_us() + u32::from(self.timing.command_us);
+        Duration::from_micros(u64::from(us))
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > HD44780<DualEnableBus<RS, EN1, EN2, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `HD44780` for a 40x4 display made of two
+    /// controllers, from 4 data pins, a register select pin, the two enable
+    /// pins and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The four db0..db3 pins and the register select pin are shared by
+    ///   both controllers.
+    /// - The first enable pin is for the controller driving rows 0 and 1,
+    ///   the second for the controller driving rows 2 and 3.
+    ///
+    /// Both controllers are initialized, then the first one is selected.
+    /// Only the selected controller shows the cursor, and
+    /// [set_cursor_xy](#method.set_cursor_xy) moves it between the two.
+    /// Commands that affect the whole display, such as
+    /// [clear](#method.clear) and [set_display](#method.set_display), go to
+    /// both controllers.
+    ///
+    pub fn new_dual_4bit<D: DelayNs>(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<DualEnableBus<RS, EN1, EN2, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(
+            DualEnableBus::from_pins(rs, en1, en2, d4, d5, d6, d7),
+            Init::FourBit,
+            Config {
+                size: DisplaySize::with_line_offsets(40, 4, [0x00, 0x40, 0x00, 0x40]),
+                ..Config::default()
+            },
+        )?;
+
+        hd.on_all_controllers(|hd| hd.init_4bit(delay))?;
+        hd.bus.select(ActiveController::First);
+        hd.hide_cursor_on_other_controller(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Send the following commands and writes to `controller`. Commands
+    /// that affect the whole display go to both controllers whatever is
+    /// selected, and the cursor stays where it is until
+    /// [set_cursor_xy](#method.set_cursor_xy) moves it.
+    ///
+    /// ```rust,ignore
+    /// lcd.select_controller(ActiveController::Both);
+    /// lcd.write_str("On rows 0 and 2", &mut delay)?;
+    /// ```
+    pub fn select_controller(&mut self, controller: ActiveController) {
+        self.bus.select(controller);
+    }
+}
+
+impl<
+        'a,
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > HD44780<SharedFourBitBus<'a, RS, EN, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `HD44780` from a handle to a 4-bit bus that
+    /// shares its register select and data pins with other displays, and a
+    /// struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The bus handle is obtained from a `SharedBusManager` and carries
+    ///   the enable pin of this display.
+    ///
+    /// Every display on the shared bus has its own independent driver, see
+    /// [SharedBusManager](bus/struct.SharedBusManager.html) for an example.
+    ///
+    pub fn new_shared<D: DelayNs>(
+        bus: SharedFourBitBus<'a, RS, EN, D4, D5, D6, D7>,
+        delay: &mut D,
+    ) -> Result<HD44780<SharedFourBitBus<'a, RS, EN, D4, D5, D6, D7>>> {
+        let mut hd = HD44780::from_parts(bus, Init::FourBit, Config::default())?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Set the functions called at each step of a transfer to this display,
+    /// see [BusHooks](bus/struct.BusHooks.html)
+    pub fn set_bus_hooks(&mut self, hooks: BusHooks) {
+        self.bus.set_hooks(hooks);
+    }
+
+    /// Set how slowly the lines to this display settle so the bus timing can
+    /// allow for it, see [LineCharacteristics](bus/struct.LineCharacteristics.html)
+    pub fn set_line_characteristics(&mut self, line: LineCharacteristics) {
+        self.bus.set_line_characteristics(line);
+    }
+
+    /// How long writing each byte to this display takes with its timing and
+    /// line characteristics, see
+    /// [byte_time](struct.HD44780.html#method.byte_time) on a 4-bit display
+    pub fn byte_time(&self) -> Duration {
+        let us = self.bus.byte_transfer_us() + u32::from(self.timing.command_us);
+        Duration::from_micros(u64::from(us))
+    }
+}
+
+impl<I2C: i2c::Write> HD44780<I2CBus<I2C>> {
+    /// Create an instance of a `HD44780` from an i2c write peripheral,
+    /// the `HD44780` I2C address and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The i2c peripheral is used to send data to the `HD44780` and to set
+    ///   its register select and enable pins.
+    ///
+    /// This mode operates on an I2C bus, using an I2C to parallel port expander
+    ///
+    pub fn new_i2c<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<HD44780<I2CBus<I2C>>> {
+        let mut hd = HD44780::from_parts(
+            I2CBus::new(i2c_bus, address),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Create an instance of a `HD44780` the same as
+    /// [new_i2c](#method.new_i2c), for a backpack whose expander isn't wired
+    /// the common way, see [BackpackPinout](bus/struct.BackpackPinout.html).
+    ///
+    pub fn new_i2c_with_pinout<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        pinout: BackpackPinout,
+        delay: &mut D,
+    ) -> Result<HD44780<I2CBus<I2C>>> {
+        let mut hd = HD44780::from_parts(
+            I2CBus::with_pinout(i2c_bus, address, pinout),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<I2C: i2c::Write + i2c::Read> HD44780<I2CBus<I2C>> {
+    /// Create an instance of a `HD44780` the same as
+    /// [new_i2c](#method.new_i2c), at the address of the first PCF8574
+    /// backpack found on the bus, see
+    /// [scan_backpack](bus/fn.scan_backpack.html) for the addresses probed.
+    /// - Returns `Error::NoDevice` if no backpack answers.
+    ///
+    pub fn new_i2c_autodetect<D: DelayNs>(
+        mut i2c_bus: I2C,
+        delay: &mut D,
+    ) -> Result<HD44780<I2CBus<I2C>>> {
+        let address = bus::scan_backpack(&mut i2c_bus)?.ok_or(Error::NoDevice)?;
+
+        HD44780::new_i2c(i2c_bus, address, delay)
+    }
+}
+
+impl<I2C: i2c::Write> HD44780<Mcp23008Bus<I2C>> {
+    /// Create an instance of a `HD44780` from an i2c write peripheral,
+    /// the I2C address of an MCP23008 port expander and a struct implementing
+    /// the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The i2c peripheral is used to configure the expander and then send
+    ///   data to the `HD44780` and set its register select and enable pins.
+    ///
+    /// This mode is for the Adafruit I2C/SPI LCD backpack, whose default
+    /// address is `0x20`. An error is returned if the expander can't be
+    /// configured.
+    ///
+    pub fn new_mcp23008<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<HD44780<Mcp23008Bus<I2C>>> {
+        let mut hd = HD44780::from_parts(
+            Mcp23008Bus::from_i2c(i2c_bus, address)?,
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<I2C: i2c::Write> HD44780<Mcp23017Bus<I2C>> {
+    /// Create an instance of a `HD44780` from an i2c write peripheral,
+    /// the I2C address of an MCP23017 port expander, the port of the expander
+    /// the display is wired to and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The i2c peripheral is used to configure the port and then send
+    ///   data to the `HD44780` and set its register select and enable pins.
+    ///
+    /// Two displays can be driven from one expander by creating one
+    /// `HD44780` per port, see [Mcp23017Bus](bus/struct.Mcp23017Bus.html).
+    /// An error is returned if the port can't be configured.
+    ///
+    pub fn new_mcp23017<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        port: Port,
+        delay: &mut D,
+    ) -> Result<HD44780<Mcp23017Bus<I2C>>> {
+        let mut hd = HD44780::from_parts(
+            Mcp23017Bus::from_i2c(i2c_bus, address, port)?,
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<SPI: spi::Write<u8>, LATCH: OutputPin> HD44780<ShiftRegisterBus<SPI, LATCH>> {
+    /// Create an instance of a `HD44780` from an spi write peripheral, the
+    /// latch pin of a 74HC595 shift register, how the register is wired to the
+    /// `HD44780` and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The spi peripheral shifts the register select, enable and data bits
+    ///   into the shift register.
+    /// - The latch pin is pulsed to move the shifted bits onto the outputs.
+    ///
+    /// This mode operates the `HD44780` in 4-bit mode through a single shift
+    /// register, so only three pins are needed. A pinout naming an output
+    /// past `7` returns `Error::OutOfBounds` before anything is sent.
+    ///
+    pub fn new_shift_register<D: DelayNs>(
+        spi: SPI,
+        latch: LATCH,
+        pinout: ShiftRegisterPinout,
+        delay: &mut D,
+    ) -> Result<HD44780<ShiftRegisterBus<SPI, LATCH>>> {
+        let mut hd = HD44780::from_parts(
+            ShiftRegisterBus::new(spi, latch, pinout)?,
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<DATA: OutputPin, CLOCK: OutputPin> HD44780<SerialBus164<DATA, CLOCK>> {
+    /// Create an instance of a `HD44780` from the data and clock pins of a
+    /// 74HC164 shift register and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The data and clock pins shift the register select and data bits
+    ///   into the shift register, and the data pin also strobes the enable
+    ///   line through a diode gate.
+    ///
+    /// This mode operates the `HD44780` in 4-bit mode with just two pins, see
+    /// [SerialBus164](bus/struct.SerialBus164.html) for the wiring.
+    ///
+    pub fn new_serial_164<D: DelayNs>(
+        data: DATA,
+        clock: CLOCK,
+        delay: &mut D,
+    ) -> Result<HD44780<SerialBus164<DATA, CLOCK>>> {
+        let mut hd = HD44780::from_parts(
+            SerialBus164::from_pins(data, clock),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<I2C: i2c::Write> HD44780<St7032Bus<I2C>> {
+    /// Create an instance of a `HD44780` from an i2c write peripheral, the
+    /// I2C address of an ST7032i based display and a struct implementing the
+    /// delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The i2c peripheral is used to send commands and data straight to
+    ///   the ST7032i, whose address is usually `0x3E`.
+    ///
+    /// The ST7032i needs its internal oscillator, voltage follower and
+    /// contrast set up before it shows anything, so this runs its own
+    /// initialization rather than the usual 4-bit or 8-bit one.
+    ///
+    pub fn new_st7032<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<HD44780<St7032Bus<I2C>>> {
+        let mut hd = HD44780::from_parts(
+            St7032Bus::new(i2c_bus, address),
+            Init::St7032,
+            Config::default(),
+        )?;
+
+        hd.init_st7032(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Set the contrast of the display, from 0 to 63. Larger values are
+    /// treated as 63.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_contrast(32, &mut delay)?;
+    /// ```
+    pub fn set_contrast<D: DelayNs>(&mut self, contrast: u8, delay: &mut D) -> Result<()> {
+        let contrast = contrast.min(0b0011_1111);
+        let function_set = self.function_set(true);
+
+        // Contrast is only reachable through the extended instruction set
+        self.write_command(function_set | 0b0000_0001, delay)?;
+
+        // The lower 4 bits of the contrast
+        self.write_command(0b0111_0000 | (contrast & 0b0000_1111), delay)?;
+
+        // The upper 2 bits of the contrast along with the booster on
+        self.write_command(0b0101_0100 | (contrast >> 4), delay)?;
+
+        // Back to the normal instruction set
+        self.write_command(function_set, delay)?;
+
+        Ok(())
+    }
+}
+
+impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
+    HD44780<St7036Bus<FourBitBus<RS, EN, D4, D5, D6, D7>>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), for an ST7036 based display such as
+    /// the EA DOGM162.
+    ///
+    /// The ST7036 needs its bias, booster, voltage follower and contrast set
+    /// up before it shows anything at 3.3 V, so this runs its own
+    /// initialization rather than the usual 4-bit one.
+    ///
+    pub fn new_4bit_st7036<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<St7036Bus<FourBitBus<RS, EN, D4, D5, D6, D7>>>> {
+        let mut hd = HD44780::from_parts(
+            St7036Bus::from_bus(FourBitBus::from_pins(rs, en, d4, d5, d6, d7)),
+            Init::St7036(false),
+            Config::default(),
+        )?;
+
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(u32::from(hd.timing.init.power_on_ms));
+
+        // The bus has to be in 4-bit mode for the set up to get through
+        hd.bus.reset_4bit(&hd.timing, delay)?;
+        hd.init_st7036(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > HD44780<St7036Bus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), for an ST7036 based display such as
+    /// the EA DOGM162.
+    ///
+    /// The ST7036 needs its bias, booster, voltage follower and contrast set
+    /// up before it shows anything at 3.3 V, so this runs its own
+    /// initialization rather than the usual 8-bit one.
+    ///
+    pub fn new_8bit_st7036<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<HD44780<St7036Bus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>>> {
+        let mut hd = HD44780::from_parts(
+            St7036Bus::from_bus(EightBitBus::from_pins(
+                rs, en, d0, d1, d2, d3, d4, d5, d6, d7,
+            )),
+            Init::St7036(true),
+            Config::default(),
+        )?;
+
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(u32::from(hd.timing.init.power_on_ms));
+
+        hd.init_st7036(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<B: DataBus> HD44780<St7036Bus<B>> {
+    /// Set the contrast of the display, from 0 to 63. Larger values are
+    /// treated as 63.
+    ///
+    /// The contrast is set from instruction table 1, which is switched back
+    /// to table 0 afterwards even if setting the contrast fails.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_contrast(24, &mut delay)?;
+    /// ```
+    pub fn set_contrast<D: DelayNs>(&mut self, contrast: u8, delay: &mut D) -> Result<()> {
+        let function_set = self.function_set(self.init.eight_bit());
+
+        // Contrast is only reachable through instruction table 1
+        self.write_command(function_set | 0b0000_0001, delay)?;
+
+        let written = self.write_st7036_contrast(contrast, delay);
+
+        // Back to instruction table 0, whether or not the contrast got
+        // through
+        let restored = self.write_command(function_set, delay);
+
+        written.and(restored)
+    }
+
+    fn write_st7036_contrast<D: DelayNs>(&mut self, contrast: u8, delay: &mut D) -> Result<()> {
+        let contrast = contrast.min(0b0011_1111);
+
+        // The lower 4 bits of the contrast
+        self.write_command(0b0111_0000 | (contrast & 0b0000_1111), delay)?;
+
+        // The upper 2 bits of the contrast along with the booster on
+        self.write_command(0b0101_0100 | (contrast >> 4), delay)
+    }
+}
+
+impl<'a, I2C: i2c::Write> HD44780<GroveRgbBus<'a, I2C>> {
+    /// Create an instance of a `HD44780` from an i2c write peripheral wired
+    /// to a Seeed Grove RGB LCD, and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `HD44780` has enough time to process commands.
+    /// - The i2c peripheral is shared between the text controller and the
+    ///   RGB backlight controller, so it is passed in a `RefCell`.
+    ///
+    /// Returns the display along with a handle to its RGB backlight.
+    ///
+    pub fn new_grove_rgb<D: DelayNs>(
+        i2c_bus: &'a RefCell<I2C>,
+        delay: &mut D,
+    ) -> Result<(HD44780<GroveRgbBus<'a, I2C>>, RgbBacklight<'a, I2C>)> {
+        let mut hd =
+            HD44780::from_parts(GroveRgbBus::new(i2c_bus), Init::GroveRgb, Config::default())?;
+
+        hd.init_grove_rgb(delay)?;
+
+        let backlight = RgbBacklight::new(i2c_bus)?;
+
+        Ok((hd, backlight))
+    }
+}
+
+impl<B> HD44780<B>
+where
+    B: DataBus + NibbleBus,
+{
+    /// Send a single 4-bit transfer, bypassing the usual split of each byte
+    /// into two nibbles. This is for sequences such as the reset by
+    /// instruction, and leaves the display out of step with the driver if
+    /// an odd number of nibbles is sent.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_raw_nibble(0x3, false, &mut delay)?;
+    /// ```
+    pub fn write_raw_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.bus.write_nibble(nibble, data, delay)
+    }
+
+    /// Bring a WS0010 into step with 4-bit transfers. It keeps its interface
+    /// mode through a reset of the host, so five zero nibbles (an instruction
+    /// it ignores in either nibble phase) line it up on a byte boundary
+    /// before the 0x2 nibble selects 4-bit mode.
+    fn sync_ws0010_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        for _ in 0..5 {
+            self.bus.write_nibble(0x0, false, delay)?;
+        }
+
+        self.bus.write_nibble(0x2, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        Ok(())
+    }
+}
+
+impl<B> HD44780<B>
+where
+    B: DataBus + Brightness,
+{
+    /// Set the backlight brightness from 0 (off) to 255 (full), on buses
+    /// that can dim it
+    ///
+    /// ```rust,ignore
+    /// lcd.set_brightness(128)?;
+    /// ```
+    pub fn set_brightness(&mut self, level: u8) -> Result<()> {
+        self.bus.set_brightness(level)
+    }
+}
+
+impl<B> HD44780<B>
+where
+    B: DataBus + Backlight,
+{
+    /// Turn the backlight on or off, on buses that can switch it
+    ///
+    /// ```rust,ignore
+    /// lcd.set_backlight(false)?;
+    /// ```
+    pub fn set_backlight(&mut self, on: bool) -> Result<()> {
+        self.bus.set_backlight(on)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        P: SetDutyCycle,
+    > HD44780<DimmerBus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>, P>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_8bit](#method.new_8bit), with the backlight dimmed by a PWM
+    /// channel.
+    /// - The backlight starts at full brightness once the display is set
+    ///   up, see [set_brightness](#method.set_brightness).
+    ///
+    pub fn new_8bit_with_dimmer<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        pwm: P,
+        delay: &mut D,
+    ) -> Result<HD44780<DimmerBus<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>, P>>> {
+        let mut hd = HD44780::from_parts(
+            DimmerBus::from_bus(
+                EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
+                pwm,
+            ),
+            Init::EightBit,
+            Config::default(),
+        )?;
+
+        hd.init_8bit(delay)?;
+        hd.set_backlight(true)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+        P: SetDutyCycle,
+    > HD44780<DimmerBus<FourBitBus<RS, EN, D4, D5, D6, D7>, P>>
+{
+    /// Create an instance of a `HD44780` the same as
+    /// [new_4bit](#method.new_4bit), with the backlight dimmed by a PWM
+    /// channel.
+    /// - The backlight starts at full brightness once the display is set
+    ///   up, see [set_brightness](#method.set_brightness).
+    ///
+    pub fn new_4bit_with_dimmer<D: DelayNs>(
+        rs: RS,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        pwm: P,
+        delay: &mut D,
+    ) -> Result<HD44780<DimmerBus<FourBitBus<RS, EN, D4, D5, D6, D7>, P>>> {
+        let mut hd = HD44780::from_parts(
+            DimmerBus::from_bus(FourBitBus::from_pins(rs, en, d4, d5, d6, d7), pwm),
+            Init::FourBit,
+            Config::default(),
+        )?;
+
+        hd.init_4bit(delay)?;
+        hd.set_backlight(true)?;
+
+        Ok(hd)
+    }
+}
+
+impl<B: DataBus, BL: OutputPin> HD44780<BacklightBus<B, BL>> {
+    /// Have `set_display` switch the backlight off along with the display,
+    /// and back on with it unless it was turned off with `set_backlight`
+    ///
+    /// ```rust,ignore
+    /// lcd.link_backlight_to_display(true)?;
+    /// ```
+    pub fn link_backlight_to_display(&mut self, linked: bool) -> Result<()> {
+        self.bus.link_to_display(linked)
+    }
+}
+
+impl<B> HD44780<B>
+where
+    B: DataBus,
+{
+    /// Run the display's set up again and put back its display and entry
+    /// modes, for when a glitch on the supply or ESD has scrambled it. The
+    /// contents of the display are cleared.
+    ///
+    /// On a display with two enable lines both controllers are set up, and
+    /// the controller that was selected stays selected.
+    ///
+    /// ```rust,ignore
+    /// lcd.reinit(&mut delay)?;
+    /// lcd.write_str("Recovered", &mut delay)?;
+    /// ```
+    pub fn reinit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.on_all_controllers(|hd| match hd.init {
+            Init::FourBit => hd.init_4bit(delay),
+            Init::EightBit => hd.init_8bit(delay),
+            Init::Ws0010(eight_bit) => {
+                if !eight_bit {
+                    hd.bus.reset_4bit(&hd.timing, delay)?;
+                }
+                hd.init_ws0010(delay)
+            }
+            Init::St7032 => hd.init_st7032(delay),
+            Init::St7036(eight_bit) => {
+                if !eight_bit {
+                    hd.bus.reset_4bit(&hd.timing, delay)?;
+                }
+                hd.init_st7036(delay)
+            }
+            Init::GroveRgb => hd.init_grove_rgb(delay),
+        })?;
+        self.hide_cursor_on_other_controller(delay)
+    }
+
+    /// Unshifts the display and sets the cursor position to 0
+    ///
+    /// ```rust,ignore
+    /// lcd.reset();
+    /// ```
+    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.home(delay)
+    }
+
+    /// Return home: unshift the display and move the cursor to position 0.
+    /// This waits as long as the instruction takes on the controller variant,
+    /// 1.52 ms on an HD44780, unless the busy flag can be read. On a display
+    /// with two enable lines this selects the controller for the top row.
+    ///
+    /// ```rust,ignore
+    /// lcd.home(&mut delay)?;
+    /// lcd.write_str("Top left", &mut delay)?;
+    /// ```
+    pub fn home<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.on_all_controllers(|hd| hd.write_slow_command(0b0000_0010, delay))?;
+
+        self.select_row(0, delay)
+    }
+
+    /// Set if the display should be on, if the cursor should be
+    /// visible, and if the cursor should blink
+    ///
+    /// Note: This is equivilent to calling all of the other relavent
+    /// methods however this operation does it all in one go to the `HD44780`
+    pub fn set_display_mode<D: DelayNs>(
+        &mut self,
+        display_mode: DisplayMode,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.display_mode = display_mode;
+
+        self.write_display_mode(delay)
+    }
+
+    /// Clear the entire display and move the cursor to position 0. Like
+    /// [home](#method.home) this waits as long as the controller variant
+    /// takes to clear, unless the busy flag can be read, and selects the
+    /// controller for the top row.
+    ///
+    /// ```rust,ignore
+    /// lcd.clear();
+    /// ```
+    pub fn clear<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.on_all_controllers(|hd| hd.write_slow_command(0b0000_0001, delay))?;
+
+        self.select_row(0, delay)
+    }
+
+    /// If enabled, automatically scroll the display when a new
+    /// character is written to the display
+    ///
+    /// ```rust,ignore
+    /// lcd.set_autoscroll(true);
+    /// ```
+    pub fn set_autoscroll<D: DelayNs>(&mut self, enabled: bool, delay: &mut D) -> Result<()> {
+        self.entry_mode.shift_mode = enabled.into();
+
+        let cmd = self.entry_mode.as_byte();
+
+        self.on_all_controllers(|hd| hd.write_command(cmd, delay))
+    }
+
+    /// Set a minimum gap between consecutive data bytes written to the
+    /// display, or `None` to write them at full speed. Gaps are rounded down
+    /// to whole microseconds, and anything over `u32::MAX` microseconds,
+    /// about 71 minutes, is cut to that.
+    ///
+    /// This spreads out the bus activity of a burst of writes, which can help
+    /// installations where the edges on long unshielded cables interfere with
+    /// other equipment. Gaps shorter than the usual 100µs command wait have no
+    /// effect. It can be changed at any time and applies from the next byte.
+    ///
+    /// ```rust,ignore
+    /// // At most 1 byte per millisecond while the radio is listening
+    /// lcd.set_pacing(Some(Duration::from_millis(1)));
+    /// lcd.write_str("Quiet", &mut delay)?;
+    ///
+    /// lcd.set_pacing(None);
+    /// ```
+    pub fn set_pacing(&mut self, gap: Option<Duration>) {
+        self.pacing = gap.map(|gap| gap.as_micros().min(u128::from(u32::MAX)) as u32);
+    }
+
+    /// How many data bytes have been held back for the
+    /// [pacing](#method.set_pacing) gap, because it was longer than the wait
+    /// for the display, whether that is the command wait or the busy flag. This counts from when the driver was made, and stops
+    /// at `u32::MAX`.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_pacing(Some(Duration::from_millis(1)));
+    /// lcd.write_str("Quiet", &mut delay)?;
+    /// assert_eq!(lcd.paced_bytes(), 5);
+    /// ```
+    pub fn paced_bytes(&self) -> u32 {
+        self.paced_bytes
+    }
+
+    /// Set how to wait for the display to finish each instruction. Displays
+    /// made with a read/write pin poll the busy flag by default, everything
+    /// else sleeps for a fixed time.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_wait_strategy(WaitStrategy::FixedDelay);
+    /// ```
+    pub fn set_wait_strategy(&mut self, strategy: WaitStrategy) {
+        self.wait_strategy = strategy;
+    }
+
+    /// Set how long to wait after each instruction and to strobe the enable
+    /// pin for, see [TimingConfig](timing/struct.TimingConfig.html). The set
+    /// up timing only takes effect when passed to a constructor such as
+    /// [new_4bit_with_timing](#method.new_4bit_with_timing).
+    ///
+    /// ```rust,ignore
+    /// lcd.set_timing(TimingConfig::fast());
+    /// ```
+    pub fn set_timing(&mut self, timing: TimingConfig) {
+        self.timing = timing;
+        self.bus.set_timing(&timing);
+    }
+
+    /// Set how many columns and rows the display has, for
+    /// [set_cursor_xy](#method.set_cursor_xy). Displays are taken to be 16x2
+    /// unless made with a size, such as with
+    /// [new_4bit_with_size](#method.new_4bit_with_size).
+    ///
+    /// More than 4 rows, or rows that don't fit in one DDRAM line, return
+    /// `Error::OutOfBounds` and keep the size as it was. With more than one
+    /// row the lines are 0x00-0x27 and 0x40-0x67, and with one row the line
+    /// is 0x00-0x4F.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_display_size(DisplaySize::new(20, 4))?;
+    /// ```
+    pub fn set_display_size(&mut self, size: DisplaySize) -> Result<()> {
+        if !size.fits_ddram() {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.size = size;
+        Ok(())
+    }
+
+    /// How many columns and rows the display has, see
+    /// [set_display_size](#method.set_display_size)
+    pub fn size(&self) -> DisplaySize {
+        self.size
+    }
+
+    /// Switch between one and two line mode, by sending the function set
+    /// again. Two lines with the 5x10 font returns `Error::Unsupported`.
+    /// One line also makes the [display size](#method.size) a single row,
+    /// so [set_cursor_xy](#method.set_cursor_xy) keeps to it. If the
+    /// function set can't be sent, the size is kept as it was.
+    ///
+    /// The datasheet has the function set fixed once the display is set up,
+    /// but most controllers and clones take it again without trouble. If
+    /// the display misbehaves afterwards, construct it with the right size
+    /// instead.
+    ///
+    /// ```rust,ignore
+    /// if one_line_module {
+    ///     lcd.set_lines(Lines::One, &mut delay)?;
+    /// }
+    /// ```
+    pub fn set_lines<D: DelayNs>(&mut self, lines: Lines, delay: &mut D) -> Result<()> {
+        let rows = match lines {
+            Lines::One => 1,
+            Lines::Two if self.font == FontMode::FiveByTen => return Err(Error::Unsupported),
+            Lines::Two => self.size.rows.max(2),
+        };
+
+        let function_set = function_set_for(self.init.eight_bit(), rows, self.font);
+        self.on_all_controllers(|hd| hd.write_command(function_set, delay))?;
+
+        self.size.rows = rows;
+        Ok(())
+    }
+
+    /// Switch between the 5x8 and 5x10 fonts, by sending the function set
+    /// again. The 5x10 font on more than one row returns
+    /// `Error::Unsupported`, so call [set_lines](#method.set_lines) first.
+    ///
+    /// The same caveat as for `set_lines` applies.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_lines(Lines::One, &mut delay)?;
+    /// lcd.set_font(FontMode::FiveByTen, &mut delay)?;
+    /// ```
+    pub fn set_font<D: DelayNs>(&mut self, font: FontMode, delay: &mut D) -> Result<()> {
+        if font == FontMode::FiveByTen && self.size.rows > 1 {
+            return Err(Error::Unsupported);
+        }
+
+        let function_set = function_set_for(self.init.eight_bit(), self.size.rows, font);
+        self.on_all_controllers(|hd| hd.write_command(function_set, delay))?;
+
+        self.font = font;
+        Ok(())
+    }
+
+    /// Switch a WS0010 OLED controller between character mode and graphics
+    /// mode, where each byte written sets a column of eight pixels. Other
+    /// controllers would take this as a cursor or display shift, so unless
+    /// the variant is `ControllerVariant::Ws0010` nothing is sent and
+    /// `Error::Unsupported` is returned.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_graphics_mode(true, &mut delay)?;
+    /// ```
+    pub fn set_graphics_mode<D: DelayNs>(&mut self, graphics: bool, delay: &mut D) -> Result<()> {
+        if self.variant != ControllerVariant::Ws0010 {
+            return Err(Error::Unsupported);
+        }
+
+        let mode = match graphics {
+            false => 0b0000_0000,
+            true => 0b0000_1000,
+        };
+
+        // Keep the internal power supply on
+        self.write_command(0b0001_0111 | mode, delay)
+    }
+
+    /// Set if the cursor should be visible
+    pub fn set_cursor_visibility<D: DelayNs>(
+        &mut self,
+        visibility: Cursor,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.display_mode.cursor_visibility = visibility;
+
+        self.write_display_mode(delay)
+    }
+
+    /// Set if the characters on the display should be visible
+    pub fn set_display<D: DelayNs>(&mut self, display: Display, delay: &mut D) -> Result<()> {
+        self.display_mode.display = display;
+
+        self.write_display_mode(delay)
+    }
+
+    /// Set if the cursor should blink
+    pub fn set_cursor_blink<D: DelayNs>(
+        &mut self,
+        blink: CursorBlink,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.display_mode.cursor_blink = blink;
+
+        self.write_display_mode(delay)
+    }
+
+    /// The display mode last sent to the display
+    ///
+    /// ```rust,ignore
+    /// if lcd.display_mode().cursor_visibility == Cursor::Visible {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn display_mode(&self) -> DisplayMode {
+        self.display_mode
+    }
+
+    /// The entry mode last sent to the display
+    pub fn entry_mode(&self) -> EntryMode {
+        self.entry_mode
+    }
+
+    /// Show the cursor if it is hidden, or hide it if it is showing
+    pub fn toggle_cursor_visibility<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        let visibility = match self.display_mode().cursor_visibility {
+            Cursor::Visible => Cursor::Invisible,
+            Cursor::Invisible => Cursor::Visible,
+        };
+
+        self.set_cursor_visibility(visibility, delay)
+    }
+
+    /// Turn the display on if it is off, or off if it is on
+    pub fn toggle_display<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        let display = match self.display_mode().display {
+            Display::On => Display::Off,
+            Display::Off => Display::On,
+        };
+
+        self.set_display(display, delay)
+    }
+
+    /// Set which way the cursor moves and if the display shifts on write
+    ///
+    /// Note: This is equivilent to calling both `set_cursor_mode` and
+    /// `set_autoscroll` however this operation does it all in one go to the
+    /// `HD44780`
+    ///
+    /// ```rust,ignore
+    /// lcd.set_entry_mode(
+    ///     EntryMode::new(CursorMode::Decrement, ShiftMode::Enabled),
+    ///     &mut delay,
+    /// )?;
+    /// ```
+    pub fn set_entry_mode<D: DelayNs>(
+        &mut self,
+        entry_mode: EntryMode,
+        delay: &mut D,
+    ) -> Result<()> {
+        self.entry_mode = entry_mode;
+
+        let cmd_byte = self.entry_mode.as_byte();
+
+        self.on_all_controllers(|hd| hd.write_command(cmd_byte, delay))
+    }
+
+    /// Set which way the cursor will move when a new character is written
+    ///
+    /// ```rust,ignore
+    /// // Move right (Default) when a new character is written
+    /// lcd.set_cursor_mode(CursorMode::Right)
+    ///
+    /// // Move left when a new character is written
+    /// lcd.set_cursor_mode(CursorMode::Left)
+    /// ```
+    pub fn set_cursor_mode<D: DelayNs>(&mut self, mode: CursorMode, delay: &mut D) -> Result<()> {
+        self.entry_mode.cursor_mode = mode;
+
+        let cmd = self.entry_mode.as_byte();
+
+        self.on_all_controllers(|hd| hd.write_command(cmd, delay))
+    }
+
+    /// Set the cursor position
+    ///
+    /// ```rust,ignore
+    /// // Move to line 2
+    /// lcd.set_cursor_pos(40)
+    /// ```
+    pub fn set_cursor_pos<D: DelayNs>(&mut self, position: u8, delay: &mut D) -> Result<()> {
+        let lower_7_bits = 0b0111_1111 & position;
+
+        self.write_command(0b1000_0000 | lower_7_bits, delay)?;
+
+        Ok(())
+    }
+
+    /// Move the cursor to `col` on `row`, counting from 0 at the top left,
+    /// using the row addresses of the display's
+    /// [size](#method.set_display_size). Positions off the display return
+    /// `Error::OutOfBounds`.
+    ///
+    /// On a display with two enable lines this also selects the controller
+    /// that drives `row`, so following writes land there, and moves the
+    /// cursor over to it.
+    ///
+    /// ```rust,ignore
+    /// // Start of the third row on a 20x4 display, DDRAM address 0x14
+    /// lcd.set_cursor_xy(0, 2, &mut delay)?;
+    /// ```
+    pub fn set_cursor_xy<D: DelayNs>(&mut self, col: u8, row: u8, delay: &mut D) -> Result<()> {
+        let line_offset = match self.size.line_offsets.get(row as usize) {
+            Some(&offset) if row < self.size.rows && col < self.size.cols => offset,
+            _ => return Err(Error::OutOfBounds),
+        };
+
+        let address = line_offset.checked_add(col).ok_or(Error::OutOfBounds)?;
+
+        self.select_row(row, delay)?;
+
+        self.set_cursor_pos(address, delay)
+    }
+
+    /// Shift just the cursor to the left or the right
+    ///
+    /// ```rust,ignore
+    /// lcd.shift_cursor(Direction::Left);
+    /// lcd.shift_cursor(Direction::Right);
+    /// ```
+    pub fn shift_cursor<D: DelayNs>(&mut self, dir: Direction, delay: &mut D) -> Result<()> {
+        let bits = match dir {
+            Direction::Left => 0b0000_0000,
+            Direction::Right => 0b0000_0100,
+        };
+
+        self.write_command(0b0001_0000 | bits | bits, delay)?;
+
+        Ok(())
+    }
+
+    /// Shift the entire display to the left or the right
+    ///
+    /// ```rust,ignore
+    /// lcd.shift_display(Direction::Left);
+    /// lcd.shift_display(Direction::Right);
+    /// ```
+    pub fn shift_display<D: DelayNs>(&mut self, dir: Direction, delay: &mut D) -> Result<()> {
+        let bits = match dir {
+            Direction::Left => 0b0000_0000,
+            Direction::Right => 0b0000_0100,
+        };
+
+        self.on_all_controllers(|hd| hd.write_command(0b0001_1000 | bits, delay))
+    }
+
+    /// Write a single character to the `HD44780`. This `char` just gets downcast to a `u8`
+    /// internally, so make sure that whatever character you're printing fits inside that range, or
+    /// you can just use [write_byte](#method.write_byte) to have the compiler check for you.
+    /// See the documentation on that function for more details about compatibility.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_char('A', &mut delay)?; // prints 'A'
+    /// ```
+    pub fn write_char<D: DelayNs>(&mut self, data: char, delay: &mut D) -> Result<()> {
+        self.write_byte(data as u8, delay)
+    }
+
+    /// Send a raw instruction byte with RS low, for controller specific
+    /// instructions the driver has no method for. This waits for the
+    /// instruction the same way the driver's own methods do, including the
+    /// longer wait clear display (0x01) and return home (0x02) take on the
+    /// controller variant.
+    ///
+    /// This is an escape hatch: the driver keeps its own copy of the display
+    /// and entry modes, which instructions sent this way can leave out of
+    /// date, so a later `set_cursor_blink` for example could undo them.
+    ///
+    /// ```rust,ignore
+    /// lcd.command(0b0001_1000, &mut delay)?; // shift the display left
+    /// ```
+    pub fn command<D: DelayNs>(&mut self, byte: u8, delay: &mut D) -> Result<()> {
+        if byte == 0b0000_0001 || byte & 0b1111_1110 == 0b0000_0010 {
+            self.write_slow_command(byte, delay)
+        } else {
+            self.write_command(byte, delay)
+        }
+    }
+
+    fn write_command<D: DelayNs>(&mut self, cmd: u8, delay: &mut D) -> Result<()> {
+        self.bus.write(cmd, false, delay)?;
+
+        // Wait for the command to be processed
+        if !self.wait_busy_flag(delay)? {
+            delay.delay_us(u32::from(self.timing.command_us));
+        }
+        Ok(())
+    }
+
+    /// Write clear display or return home, waiting as long as they take on
+    /// the controller variant
+    fn write_slow_command<D: DelayNs>(&mut self, cmd: u8, delay: &mut D) -> Result<()> {
+        self.bus.write(cmd, false, delay)?;
+
+        // Wait for the command to be processed
+        if !self.wait_busy_flag(delay)? {
+            delay.delay_us(u32::from(
+                self.variant.clear_us().max(self.timing.command_us),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run `f` with every controller on the bus selected, then go back to
+    /// the controller selected before, whether or not `f` succeeded. Buses
+    /// to a single controller just run `f`.
+    fn on_all_controllers<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let selected = match self.bus.selected_controller() {
+            Some(selected) => selected,
+            None => return f(self),
+        };
+
+        self.bus.select_controller(ActiveController::Both);
+        let result = f(self);
+        self.bus.select_controller(selected);
+
+        result
+    }
+
+    /// Write the display mode to the selected controller, and to any other
+    /// controller without the cursor, so only one cursor shows
+    fn write_display_mode<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.write_command(self.display_mode.as_byte(), delay)?;
+
+        self.hide_cursor_on_other_controller(delay)
+    }
+
+    /// Write the display mode with the cursor hidden to the controller that
+    /// isn't selected. Does nothing with both or only one controller.
+    fn hide_cursor_on_other_controller<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        let (selected, other) = match self.bus.selected_controller() {
+            Some(ActiveController::First) => (ActiveController::First, ActiveController::Second),
+            Some(ActiveController::Second) => (ActiveController::Second, ActiveController::First),
+            _ => return Ok(()),
+        };
+
+        let hidden = DisplayMode {
+            cursor_visibility: Cursor::Invisible,
+            cursor_blink: CursorBlink::Off,
+            ..self.display_mode
+        };
+
+        self.bus.select_controller(other);
+        let result = self.write_command(hidden.as_byte(), delay);
+        self.bus.select_controller(selected);
+
+        result
+    }
+
+    /// Select the controller for `row`, moving the cursor over if that is
+    /// a different controller
+    fn select_row<D: DelayNs>(&mut self, row: u8, delay: &mut D) -> Result<()> {
+        let selected = self.bus.selected_controller();
+        self.bus.select_row(row);
+
+        if self.bus.selected_controller() != selected {
+            self.write_display_mode(delay)?;
+        }
+        Ok(())
+    }
+
+    /// Poll the busy flag until the display is ready for the next
+    /// instruction. Returns false without waiting if the wait strategy is a
+    /// fixed delay or the bus can't read.
+    fn wait_busy_flag<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool> {
+        if self.wait_strategy != WaitStrategy::BusyFlag {
+            return Ok(false);
+        }
+
+        for _ in 0..BUSY_POLLS {
+            match self.bus.read(false, delay) {
+                Ok(status) if status & 0b1000_0000 == 0 => return Ok(true),
+                Ok(_) => delay.delay_us(u32::from(BUSY_BACKOFF_US)),
+                Err(Error::ReadUnsupported) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::BusyTimeout)
+    }
+
+    /// Wrap `bus` in a driver set up as `config` says, without sending
+    /// anything to the display. Every constructor starts here, then runs
+    /// the set up `init` names.
+    fn from_parts(bus: B, init: Init, config: Config) -> Result<HD44780<B>> {
+        if config.font == FontMode::FiveByTen && config.size.rows > 1 {
+            return Err(Error::Unsupported);
+        }
+
+        if !config.size.fits_ddram() {
+            return Err(Error::OutOfBounds);
+        }
+
+        let mut hd = HD44780 {
+            bus,
+            entry_mode: EntryMode::default(),
+            display_mode: config.display_mode,
+            pacing: None,
+            paced_bytes: 0,
+            wait_strategy: WaitStrategy::FixedDelay,
+            timing: config.timing,
+            variant: config.variant,
+            init,
+            font: config.font,
+            size: config.size,
+        };
+        hd.bus.set_timing(&config.timing);
+
+        Ok(hd)
+    }
+
+    fn init_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(u32::from(self.timing.init.power_on_ms));
+
+        self.function_set_4bit(delay)?;
+
+        // A slow controller can miss the switch to 4-bit mode, so go through
+        // it once more if the display says so
+        if !self.left_8bit_mode(delay)? {
+            self.function_set_4bit(delay)?;
+        }
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.bus.write(0x08, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Clear Display
+        self.bus.write(0x01, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(
+            self.timing.init.clear_us.max(self.variant.clear_us()),
+        ));
+
+        // Set entry mode
+        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Move the cursor to beginning of first line
+        self.bus.write(0x80, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.bus.write(self.display_mode.as_byte(), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        Ok(())
+    }
+
+    fn function_set_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // Initialize Lcd in 4-bit mode
+        self.bus.reset_4bit(&self.timing, delay)?;
+
+        // Sets 4-bit operation, the number of lines and the font
+        self.bus.write(self.function_set(false), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        if self.variant.repeats_function_set() {
+            self.bus.write(self.function_set(false), false, delay)?;
+
+            // Wait for the command to be processed
+            delay.delay_us(u32::from(self.timing.command_us));
+        }
+
+        Ok(())
+    }
+
+    /// The function set instruction for the bus width, with one line for a
+    /// single row display and two otherwise
+    fn function_set(&self, eight_bit: bool) -> u8 {
+        function_set_for(eight_bit, self.size.rows, self.font)
+    }
+
+    /// Check the controller takes instructions as two nibbles by moving the
+    /// address counter to 0x45 and reading it back. A controller still in
+    /// 8-bit mode takes the 0xC and 0x5 nibbles as two instructions of
+    /// their own and ends up somewhere else. Buses that can't read always
+    /// pass, without anything being sent.
+    fn left_8bit_mode<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool> {
+        match self.bus.read(false, delay) {
+            Err(Error::ReadUnsupported) => return Ok(true),
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        self.bus.write(0x80 | 0x45, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        Ok(self.bus.read(false, delay)? == 0x45)
+    }
+
+    /// Set up a WS0010 OLED controller, starting from the function set for
+    /// its bus width
+    fn init_ws0010<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // Sets the bus width, the lines and the English / Japanese font table
+        self.write_command(self.function_set(self.init.eight_bit()), delay)?;
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.write_command(0b0000_1000, delay)?;
+
+        // Character mode, with the internal power supply on
+        self.write_command(0b0001_0111, delay)?;
+
+        // Clear Display, which takes the WS0010 up to 6.2 ms
+        self.write_slow_command(0b0000_0001, delay)?;
+
+        // Move the cursor to beginning of first line
+        self.write_slow_command(0b0000_0010, delay)?;
+
+        // Set entry mode
+        self.write_command(self.entry_mode.as_byte(), delay)?;
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.write_command(self.display_mode.as_byte(), delay)?;
+
+        Ok(())
+    }
+
+    // Follow the 8-bit setup procedure as specified in the HD44780 datasheet
+    fn init_8bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(u32::from(self.timing.init.power_on_ms));
+
+        // Initialize Lcd in 8-bit mode
+        self.bus.write(0b0011_0000, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_ms(u32::from(self.timing.init.function_set_ms));
+
+        // Sets 8-bit operation, the number of lines and the font
+        self.bus.write(self.function_set(true), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        if self.variant.repeats_function_set() {
+            self.bus.write(self.function_set(true), false, delay)?;
+
+            // Wait for the command to be processed
+            delay.delay_us(u32::from(self.timing.command_us));
+        }
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.bus.write(0b0000_1000, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Clear Display
+        self.bus.write(0b0000_0001, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(
+            self.timing.init.clear_us.max(self.variant.clear_us()),
+        ));
+
+        // Set entry mode
+        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Move the cursor to beginning of first line
+        self.bus.write(0b1000_0000, false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.bus.write(self.display_mode.as_byte(), false, delay)?;
+
+        // Wait for the command to be processed
+        delay.delay_us(u32::from(self.timing.command_us));
+
+        Ok(())
+    }
+
+    /// Set up an ST7032 over I2C, turning on its booster and voltage
+    /// follower
+    fn init_st7032<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(40);
+
+        let function_set = self.function_set(true);
+
+        // Sets 8-bit operation, the lines and the font
+        self.write_command(function_set, delay)?;
+
+        // Same again with the extended instruction set selected
+        self.write_command(function_set | 0b0000_0001, delay)?;
+
+        // Internal oscillator frequency
+        self.write_command(0b0001_0100, delay)?;
+
+        // Default contrast of 32, along with the booster on
+        self.write_command(0b0111_0000, delay)?;
+        self.write_command(0b0101_0110, delay)?;
+
+        // Voltage follower on
+        self.write_command(0b0110_1100, delay)?;
+
+        // Wait for the power supply to stabilize
+        delay.delay_ms(200);
+
+        // Back to the normal instruction set
+        self.write_command(function_set, delay)?;
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.write_command(0b0000_1000, delay)?;
+
+        // Clear Display
+        self.write_command(0b0000_0001, delay)?;
+        delay.delay_ms(2);
+
+        // Set entry mode
+        self.write_command(self.entry_mode.as_byte(), delay)?;
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.write_command(self.display_mode.as_byte(), delay)?;
+
+        Ok(())
+    }
+
+    /// Set up an ST7036, starting from the function set for its bus width
+    fn init_st7036<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        let function_set = self.function_set(self.init.eight_bit());
+
+        // Sets the bus width and the lines, with instruction table 1 selected
+        self.write_command(function_set | 0b0000_0001, delay)?;
+
+        // 1/5 bias for 2 lines
+        self.write_command(0b0001_0100, delay)?;
+
+        // The upper 2 bits of a default contrast of 24, along with the
+        // booster on
+        self.write_command(0b0101_0101, delay)?;
+
+        // Voltage follower on
+        self.write_command(0b0110_1101, delay)?;
+
+        // The lower 4 bits of the default contrast
+        self.write_command(0b0111_1000, delay)?;
+
+        // Wait for the power supply to stabilize
+        delay.delay_ms(200);
+
+        // Back to instruction table 0
+        self.write_command(function_set, delay)?;
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.write_command(0b0000_1000, delay)?;
+
+        // Clear Display
+        self.write_command(0b0000_0001, delay)?;
+        delay.delay_ms(2);
+
+        // Set entry mode
+        self.write_command(self.entry_mode.as_byte(), delay)?;
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.write_command(self.display_mode.as_byte(), delay)?;
+
+        Ok(())
+    }
+
+    /// Set up the controller on a Grove RGB LCD module
+    fn init_grove_rgb<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        // These modules take longer than a bare controller to come out of
+        // power on reset
+        delay.delay_ms(50);
+
+        // Sets 2 lines of 5x8 chars, repeated with the waits the module
+        // expects before it is sure to have taken it
+        self.write_command(0b0010_1000, delay)?;
+        delay.delay_ms(5);
+
+        self.write_command(0b0010_1000, delay)?;
+        delay.delay_us(150);
+
+        self.write_command(0b0010_1000, delay)?;
+        self.write_command(0b0010_1000, delay)?;
+
+        // Keep the display off so whatever was in DDRAM at power on is never
+        // shown
+        self.write_command(0b0000_1000, delay)?;
+
+        // Clear Display
+        self.write_command(0b0000_0001, delay)?;
+        delay.delay_ms(2);
+
+        // Set entry mode
+        self.write_command(self.entry_mode.as_byte(), delay)?;
+
+        // Now that the display is blank, turn it on as the display mode says
+        self.write_command(self.display_mode.as_byte(), delay)?;
+
+        Ok(())
+    }
+
+    /// Writes a string to the HD44780. Internally, this just prints the string byte-by-byte, so
+    /// make sure the characters in the string fit in a normal `u8`. See the documentation on
+    /// [write_byte](#method.write_byte) for more details on compatibility.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_str("Hello, World!", &mut delay)?;
+    /// ```
+    pub fn write_str<D: DelayNs>(&mut self, string: &str, delay: &mut D) -> Result<()> {
+        self.write_bytes(string.as_bytes(), delay)
+    }
+
+    /// Writes a sequence of bytes to the HD44780. See the documentation on the
+    /// [write_byte](#method.write_byte) function for more details about compatibility.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_bytes(b"Hello, World!", &mut delay)?;
+    /// ```
+    pub fn write_bytes<D: DelayNs>(&mut self, string: &[u8], delay: &mut D) -> Result<()> {
+        for &b in string {
+            self.write_byte(b, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a single byte to the HD44780. These usually map to ASCII characters when printed on the
+    /// screen, but not always. While it varies depending on the ROM of the LCD, `0x20u8..=0x5b`
+    /// and `0x5d..=0x7d` should map to their standard ASCII characters. That is, all the printable
+    /// ASCII characters work, excluding `\` and `~`, which are usually displayed as `¥` and `🡢`
+    /// respectively.
+    ///
+    /// More information can be found in the Hitachi datasheets for the HD44780.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_byte(b'A', &mut delay)?; // prints 'A'
+    /// lcd.write_byte(b'\\', &mut delay)?; // usually prints ¥
+    /// lcd.write_byte(b'~', &mut delay)?; // usually prints 🡢
+    /// lcd.write_byte(b'\x7f', &mut delay)?; // usually prints 🡠
+    /// ```
+    pub fn write_byte<D: DelayNs>(&mut self, data: u8, delay: &mut D) -> Result<()> {
+        self.bus.write(data, true, delay)?;
+
+        // The wait for the display, with nothing left to wait for once the
+        // busy flag has cleared
+        let wait = match self.wait_busy_flag(delay)? {
+            true => 0,
+            false => u32::from(self.timing.command_us),
+        };
+
+        // Hold off the next byte for the pacing gap if that is longer
+        let gap = self.pacing.unwrap_or(0);
+        if gap > wait {
+            delay.delay_us(gap);
+            self.paced_bytes = self.paced_bytes.saturating_add(1);
+        } else if wait > 0 {
+            delay.delay_us(wait);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the byte at the current address in DDRAM or CGRAM, moving the
+    /// address along the same way a write would. This needs a bus with a
+    /// read/write pin, such as one made with [new_4bit_rw](#method.new_4bit_rw),
+    /// and returns `Error::ReadUnsupported` otherwise.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_cursor_pos(0, &mut delay)?;
+    /// let first = lcd.read_byte(&mut delay)?;
+    /// ```
+    pub fn read_byte<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8> {
+        let byte = self.bus.read(true, delay)?;
+
+        // Wait for the address counter to be moved along
+        if !self.wait_busy_flag(delay)? {
+            delay.delay_us(u32::from(self.timing.command_us));
+        }
+
+        Ok(byte)
+    }
+
+    /// Reads the address counter, which is where in DDRAM or CGRAM the next
+    /// byte will be written or read. This needs a bus with a read/write pin
+    /// and returns `Error::ReadUnsupported` otherwise.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_str("Hello", &mut delay)?;
+    /// let next = lcd.read_address(&mut delay)?;
+    /// ```
+    pub fn read_address<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8> {
+        let status = self.bus.read(false, delay)?;
+
+        // Mask off the busy flag
+        Ok(status & 0b0111_1111)
+    }
+
+    /// Reads `buf.len()` characters out of DDRAM starting at address `start`,
+    /// then puts the address counter back where it was. Reads that run past
+    /// the end of the first line carry on from the start of the second line
+    /// (`0x27` is followed by `0x40`), the same as writes do.
+    ///
+    /// The address counter is restored as a DDRAM address, so this shouldn't
+    /// be used in the middle of writing to CGRAM. This needs a bus with a
+    /// read/write pin and returns `Error::ReadUnsupported` otherwise.
+    ///
+    /// ```rust,ignore
+    /// let mut line = [0u8; 16];
+    /// lcd.read_ddram(0x40, &mut line, &mut delay)?;
+    /// ```
+    pub fn read_ddram<D: DelayNs>(
+        &mut self,
+        start: u8,
+        buf: &mut [u8],
+        delay: &mut D,
+    ) -> Result<()> {
+        self.read_from(0b1000_0000 | (start & 0b0111_1111), buf, delay)
+    }
+
+    /// Upload a custom character to CGRAM slot `index`, one byte per row
+    /// from the top, using the lower 5 bits of each. With the 5x8 font there
+    /// are slots 0 to 7 and 8 rows to a character. With the
+    /// [5x10 font](enum.FontMode.html) there are slots 0 to 3 and 11 rows.
+    /// Slots past the last return `Error::OutOfBounds`, and the wrong number
+    /// of rows returns `Error::Unsupported`.
+    ///
+    /// Afterwards the cursor is put back where it was when the bus can read
+    /// the address counter, and moved to the start of the first line
+    /// otherwise.
+    ///
+    /// The character is shown by writing its slot number as a byte, or
+    /// twice its slot number with the 5x10 font. Slot 0 is awkward to put in
+    /// a `&str`, so `write_byte(0)` is the easier way, or use 8 which shows
+    /// the same character.
+    ///
+    /// ```rust,ignore
+    /// const DEGREE: [u8; 8] = [0x06, 0x09, 0x09, 0x06, 0x00, 0x00, 0x00, 0x00];
+    /// lcd.create_char(1, DEGREE, &mut delay)?;
+    /// lcd.write_str("21", &mut delay)?;
+    /// lcd.write_byte(1, &mut delay)?;
+    /// ```
+    pub fn create_char<P: AsRef<[u8]>, D: DelayNs>(
+        &mut self,
+        index: u8,
+        pattern: P,
+        delay: &mut D,
+    ) -> Result<()> {
+        if index >= self.font.slots() {
+            return Err(Error::OutOfBounds);
+        }
+        if pattern.as_ref().len() != self.font.rows() {
+            return Err(Error::Unsupported);
+        }
+
+        self.on_all_controllers(|hd| hd.write_cgram(index * hd.font.stride(), &[pattern], delay))
+    }
+
+    /// Upload all 8 custom characters at once, `bank[0]` to slot 0 and so
+    /// on. This sets the CGRAM address once rather than for each character,
+    /// so takes fewer transfers than 8 calls to
+    /// [create_char](#method.create_char). This is for the 5x8 font, and
+    /// returns `Error::Unsupported` with the 5x10 font.
+    ///
+    /// ```rust,ignore
+    /// lcd.load_custom_chars(&BAR_GRAPH, &mut delay)?;
+    /// ```
+    pub fn load_custom_chars<D: DelayNs>(
+        &mut self,
+        bank: &[[u8; 8]; 8],
+        delay: &mut D,
+    ) -> Result<()> {
+        self.load_custom_chars_partial(bank, delay)
+    }
+
+    /// Upload up to 8 custom characters at once, starting from slot 0, the
+    /// same as [load_custom_chars](#method.load_custom_chars). More than 8
+    /// return `Error::OutOfBounds` without uploading any.
+    ///
+    /// ```rust,ignore
+    /// lcd.load_custom_chars_partial(&[DEGREE, BELL], &mut delay)?;
+    /// ```
+    pub fn load_custom_chars_partial<D: DelayNs>(
+        &mut self,
+        chars: &[[u8; 8]],
+        delay: &mut D,
+    ) -> Result<()> {
+        if chars.len() > 8 {
+            return Err(Error::OutOfBounds);
+        }
+        if self.font != FontMode::FiveByEight {
+            return Err(Error::Unsupported);
+        }
+
+        self.on_all_controllers(|hd| hd.write_cgram(0, chars, delay))
+    }
+
+    /// Reads back the rows of the custom character in CGRAM slot `index`,
+    /// then puts the DDRAM address counter back where it was. Only the lower
+    /// 5 bits of each row are part of the character.
+    ///
+    /// As with [create_char](#method.create_char) there are slots 0 to 7 and
+    /// 8 rows to a character with the 5x8 font, and slots 0 to 3 and 11 rows
+    /// with the 5x10 font. Slots past the last return `Error::OutOfBounds`,
+    /// and an `out` of the wrong length returns `Error::Unsupported`.
+    ///
+    /// This needs a bus with a read/write pin and returns
+    /// `Error::ReadUnsupported` otherwise.
+    ///
+    /// ```rust,ignore
+    /// let mut rows = [0u8; 8];
+    /// lcd.read_cgram(0, &mut rows, &mut delay)?;
+    /// ```
+    pub fn read_cgram<D: DelayNs>(
+        &mut self,
+        index: u8,
+        out: &mut [u8],
+        delay: &mut D,
+    ) -> Result<()> {
+        if index >= self.font.slots() {
+            return Err(Error::OutOfBounds);
+        }
+        if out.len() != self.font.rows() {
+            return Err(Error::Unsupported);
+        }
+
+        self.read_from(0b0100_0000 | (index * self.font.stride()), out, delay)
+    }
+
+    /// Checks that the custom character in CGRAM slot `index` matches
+    /// `pattern`, to catch uploads that the display didn't take. Only the
+    /// lower 5 bits of each row are compared. Slots and rows are checked as
+    /// for [read_cgram](#method.read_cgram).
+    ///
+    /// ```rust,ignore
+    /// if !lcd.verify_char(0, &HEART, &mut delay)? {
+    ///     // upload it again
+    /// }
+    /// ```
+    pub fn verify_char<D: DelayNs>(
+        &mut self,
+        index: u8,
+        pattern: &[u8],
+        delay: &mut D,
+    ) -> Result<bool> {
+        let mut rows = [0u8; 11];
+        let rows = match rows.get_mut(..pattern.len()) {
+            Some(rows) => rows,
+            None => return Err(Error::Unsupported),
+        };
+        self.read_cgram(index, rows, delay)?;
+
+        Ok(rows
+            .iter()
+            .zip(pattern.iter())
+            .all(|(row, expected)| row & 0b0001_1111 == expected & 0b0001_1111))
+    }
+
+    /// Checks that a display is actually connected, by writing to a spare
+    /// cell of DDRAM and reading it back. The cell and the address counter
+    /// are put back the way they were afterwards.
+    ///
+    /// Without a display every write still succeeds, so this is the only way
+    /// to tell that the cable has come loose. It needs a bus with a read/write
+    /// pin and returns `Error::ReadUnsupported` otherwise.
+    ///
+    /// ```rust,ignore
+    /// if !lcd.probe(&mut delay)? {
+    ///     // nothing on the other end of the cable
+    /// }
+    /// ```
+    pub fn probe<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool> {
+        match self.write_read_back(delay) {
+            // A display that never stops being busy isn't answering
+            Err(Error::BusyTimeout) => Ok(false),
+            result => result,
+        }
+    }
+
+    /// Write a pattern and its inverse to the probe cell, reporting whether
+    /// both read back
+    fn write_read_back<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool> {
+        let address = self.read_address(delay)?;
+
+        // Count up without shifting the display, whatever the entry mode is
+        let entry_mode = self.entry_mode.as_byte();
+        if entry_mode != 0b0000_0110 {
+            self.write_command(0b0000_0110, delay)?;
+        }
+
+        self.set_cursor_pos(PROBE_ADDRESS, delay)?;
+        let original = self.read_byte(delay)?;
+
+        let mut matched = true;
+        for &pattern in &[0b0101_0101, 0b1010_1010] {
+            self.set_cursor_pos(PROBE_ADDRESS, delay)?;
+            self.write_byte(pattern, delay)?;
+
+            self.set_cursor_pos(PROBE_ADDRESS, delay)?;
+            matched &= self.read_byte(delay)? == pattern;
+        }
+
+        self.set_cursor_pos(PROBE_ADDRESS, delay)?;
+        self.write_byte(original, delay)?;
+
+        if entry_mode != 0b0000_0110 {
+            self.write_command(entry_mode, delay)?;
+        }
+
+        self.set_cursor_pos(address, delay)?;
+
+        Ok(matched)
+    }
+
+    /// Write `chars` to CGRAM one after the other from `address`, then
+    /// restore the DDRAM address counter if it can be read, or go to DDRAM
+    /// address 0 if not
+    fn write_cgram<P: AsRef<[u8]>, D: DelayNs>(
+        &mut self,
+        address: u8,
+        chars: &[P],
+        delay: &mut D,
+    ) -> Result<()> {
+        let ddram_address = match self.read_address(delay) {
+            Ok(address) => address,
+            Err(Error::ReadUnsupported) => 0,
+            Err(e) => return Err(e),
+        };
+
+        // Make sure the address counter counts up through the rows
+        let decrementing = self.entry_mode.cursor_mode == CursorMode::Decrement;
+        if decrementing {
+            self.write_command(self.entry_mode.as_byte() | 0b0000_0010, delay)?;
+        }
+
+        self.write_command(0b0100_0000 | address, delay)?;
+        for pattern in chars {
+            self.write_bytes(pattern.as_ref(), delay)?;
+        }
+
+        if decrementing {
+            self.write_command(self.entry_mode.as_byte(), delay)?;
+        }
+
+        self.set_cursor_pos(ddram_address, delay)
+    }
+
+    /// Set the address with `address_cmd`, read `buf.len()` bytes from there
+    /// and then restore the DDRAM address counter
+    fn read_from<D: DelayNs>(
+        &mut self,
+        address_cmd: u8,
+        buf: &mut [u8],
+        delay: &mut D,
+    ) -> Result<()> {
+        let address = self.read_address(delay)?;
+
+        // Reads move the address counter the same way as writes, so make sure
+        // it counts up through the buffer
+        let decrementing = self.entry_mode.cursor_mode == CursorMode::Decrement;
+        if decrementing {
+            self.write_command(self.entry_mode.as_byte() | 0b0000_0010, delay)?;
+        }
+
+        self.write_command(address_cmd, delay)?;
+
+        for byte in buf.iter_mut() {
+            *byte = self.read_byte(delay)?;
+        }
+
+        if decrementing {
+            self.write_command(self.entry_mode.as_byte(), delay)?;
+        }
+
+        self.set_cursor_pos(address, delay)?;
 
         Ok(())
     }
 
-    /// Set which way the cursor will move when a new character is written
-    ///
-    /// ```rust,ignore
-    /// // Move right (Default) when a new character is written
-    /// lcd.set_cursor_mode(CursorMode::Right)
-    ///
-    /// // Move left when a new character is written
-    /// lcd.set_cursor_mode(CursorMode::Left)
-    /// ```
-    pub fn set_cursor_mode<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        mode: CursorMode,
-        delay: &mut D,
-    ) -> Result<()> {
-        self.entry_mode.cursor_mode = mode;
+    /// Consume the driver and give back the bus, so the pins or peripheral
+    /// inside can be put to other use while the display is off.
+    ///
+    /// ```rust,ignore
+    /// let (rs, en, d4, d5, d6, d7) = lcd.destroy().destroy();
+    /// ```
+    pub fn destroy(self) -> B {
+        self.bus
+    }
+
+    // Pulse the enable pin telling the HD44780 that we something for it
+    /*fn pulse_enable(&mut self) {
+        self.en.set_high();
+        self.delay.delay_ms(15u8);
+        self.en.set_low();
+    }*/
+}
+
+//impl<B> Write for HD44780<B>
+//where
+//    B: DataBus,
+//{
+//    fn write_str(&mut self, string: &str) -> Result {
+//        for c in string.chars() {
+//            self.write_char(c, delay);
+//        }
+//        Ok(())
+//    }
+//}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{self, Controller, Event, EventLog};
+    use crate::timing::InitTiming;
+    use entry_mode::ShiftMode;
+
+    fn lcd(log: &EventLog) -> HD44780<mock::Bus> {
+        HD44780::from_parts(log.bus(), Init::FourBit, Config::default()).unwrap()
+    }
+
+    fn simulated(controller: &Controller) -> HD44780<mock::ControllerBus> {
+        let mut lcd =
+            HD44780::from_parts(controller.bus(), Init::FourBit, Config::default()).unwrap();
+        lcd.wait_strategy = WaitStrategy::BusyFlag;
+        lcd
+    }
+
+    fn delays(log: &EventLog) -> Vec<u32> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::DelayUs(us) => Some(us),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn commands(log: &EventLog) -> Vec<u8> {
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Command(cmd) => Some(cmd),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether `cmd` is a display control command that turns the display on
+    fn shows_display(cmd: u8) -> bool {
+        cmd & 0b1111_1000 == 0b0000_1000 && cmd & 0b0000_0100 != 0
+    }
+
+    #[test]
+    fn display_stays_off_until_cleared_4bit() {
+        let log = EventLog::new();
+        lcd(&log).init_4bit(&mut log.delay()).unwrap();
+
+        let commands = commands(&log);
+        let clear = commands.iter().position(|&cmd| cmd == 0x01).unwrap();
+
+        assert!(commands.contains(&0x08));
+        assert!(!commands[..clear].iter().any(|&cmd| shows_display(cmd)));
+        assert!(shows_display(*commands.last().unwrap()));
+    }
+
+    #[test]
+    fn display_stays_off_until_cleared_8bit() {
+        let log = EventLog::new();
+        lcd(&log).init_8bit(&mut log.delay()).unwrap();
+
+        let commands = commands(&log);
+        let clear = commands.iter().position(|&cmd| cmd == 0x01).unwrap();
+
+        assert!(commands.contains(&0x08));
+        assert!(!commands[..clear].iter().any(|&cmd| shows_display(cmd)));
+        assert!(shows_display(*commands.last().unwrap()));
+    }
+
+    #[test]
+    fn display_can_stay_off_until_revealed() {
+        let log = EventLog::new();
+        let config = Config {
+            display_mode: DisplayMode {
+                display: Display::Off,
+                ..DisplayMode::default()
+            },
+            ..Config::default()
+        };
+        let mut lcd = HD44780::from_parts(log.bus(), Init::FourBit, config).unwrap();
+
+        lcd.init_4bit(&mut log.delay()).unwrap();
+        assert!(!commands(&log).iter().any(|&cmd| shows_display(cmd)));
+        assert_eq!(lcd.display_mode().display, Display::Off);
+
+        lcd.write_str("Hi", &mut log.delay()).unwrap();
+        lcd.set_display(Display::On, &mut log.delay()).unwrap();
+        assert!(shows_display(*commands(&log).last().unwrap()));
+    }
+
+    #[test]
+    fn default_timing_waits_out_the_init_clear() {
+        let log = EventLog::new();
+        lcd(&log).init_4bit(&mut log.delay()).unwrap();
+
+        assert_eq!(
+            delays(&log),
+            [15000, 5000, 100, 100, 100, 1520, 100, 100, 100]
+        );
+    }
+
+    #[test]
+    fn relaxed_timing_stretches_every_wait() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_timing(TimingConfig::relaxed());
+
+        lcd.init_8bit(&mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [45000, 15000, 300, 300, 4560, 300, 300, 300]);
+
+        log.clear();
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+        lcd.clear(&mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [300, 1520]);
+    }
+
+    #[test]
+    fn conservative_init_timing_only_stretches_set_up() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_timing(TimingConfig {
+            init: InitTiming::conservative(),
+            ..TimingConfig::default()
+        });
+
+        lcd.init_4bit(&mut log.delay()).unwrap();
+        assert_eq!(
+            delays(&log),
+            [50000, 10000, 100, 100, 100, 2000, 100, 100, 100]
+        );
+    }
+
+    #[test]
+    fn function_set_is_not_repeated_once_in_4bit_mode() {
+        let controller = Controller::new();
+        simulated(&controller).init_4bit(&mut mock::Delay).unwrap();
+
+        let commands = controller.commands();
+        assert_eq!(commands[..4], [0x33, 0x32, 0x28, 0xC5]);
+        assert_eq!(commands.iter().filter(|&&cmd| cmd == 0x33).count(), 1);
+    }
+
+    /// A controller that missed the switch to 4-bit mode the first time
+    /// round, so the address counter doesn't read back as set
+    #[derive(Default)]
+    struct MissedFunctionSet {
+        commands: Vec<u8>,
+    }
+
+    impl DataBus for MissedFunctionSet {
+        fn write<D: DelayNs>(&mut self, byte: u8, _data: bool, _delay: &mut D) -> Result<()> {
+            self.commands.push(byte);
+            Ok(())
+        }
+
+        fn read<D: DelayNs>(&mut self, _data: bool, _delay: &mut D) -> Result<u8> {
+            Ok(0x0C)
+        }
+    }
+
+    #[test]
+    fn function_set_is_retried_once_when_still_in_8bit_mode() {
+        let mut lcd = HD44780::from_parts(
+            MissedFunctionSet::default(),
+            Init::FourBit,
+            Config::default(),
+        )
+        .unwrap();
+
+        lcd.init_4bit(&mut mock::Delay).unwrap();
+        assert_eq!(
+            lcd.bus.commands[..8],
+            [0x33, 0x32, 0x28, 0xC5, 0x33, 0x32, 0x28, 0x08]
+        );
+    }
+
+    #[test]
+    fn command_waits_like_the_driver_methods() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.command(0b0001_1000, &mut log.delay()).unwrap();
+        lcd.command(0x01, &mut log.delay()).unwrap();
+        lcd.command(0x03, &mut log.delay()).unwrap();
+
+        assert_eq!(
+            log.events(),
+            [
+                Event::Command(0b0001_1000),
+                Event::DelayUs(100),
+                Event::Command(0x01),
+                Event::DelayUs(1520),
+                Event::Command(0x03),
+                Event::DelayUs(1520),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_waits_as_long_as_the_variant_clears() {
+        let log = EventLog::new();
+        let mut lcd = lcd_for(&log, ControllerVariant::Splc780);
+
+        lcd.command(0x01, &mut log.delay()).unwrap();
+        lcd.command(0x02, &mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [2000, 2000]);
+    }
+
+    #[test]
+    fn command_polls_the_busy_flag_when_it_can() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+        controller.set_busy_reads(3);
+
+        lcd.command(0x01, &mut mock::Delay).unwrap();
+        assert_eq!(controller.commands(), [0x01]);
+        assert_eq!(controller.status_reads(), 4);
+    }
+
+    #[test]
+    fn ws0010_init_turns_the_power_on() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.init = Init::Ws0010(true);
+        lcd.init_ws0010(&mut log.delay()).unwrap();
+
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(commands(&log), [0x38, 0x08, 0x17, 0x01, 0x02, 0x06, on]);
+    }
+
+    #[test]
+    fn ws0010_clear_waits_for_the_oled() {
+        let log = EventLog::new();
+        let mut lcd = lcd_for(&log, ControllerVariant::Ws0010);
+
+        lcd.clear(&mut log.delay()).unwrap();
+        lcd.home(&mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [6200, 6200]);
+    }
+
+    #[test]
+    fn graphics_mode_keeps_the_power_on() {
+        let log = EventLog::new();
+        let mut lcd = lcd_for(&log, ControllerVariant::Ws0010);
+
+        lcd.set_graphics_mode(true, &mut log.delay()).unwrap();
+        lcd.set_graphics_mode(false, &mut log.delay()).unwrap();
+
+        assert_eq!(commands(&log), [0x1F, 0x17]);
+    }
+
+    #[test]
+    fn graphics_mode_is_only_sent_to_a_ws0010() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        assert_eq!(
+            lcd.set_graphics_mode(true, &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+        assert!(commands(&log).is_empty());
+    }
+
+    #[test]
+    fn ws0010_set_up_waits_once_for_clear_and_home() {
+        let log = EventLog::new();
+        let mut lcd = lcd_for(&log, ControllerVariant::Ws0010);
+        lcd.init = Init::Ws0010(true);
+
+        lcd.init_ws0010(&mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [100, 100, 100, 6200, 6200, 100, 100]);
+    }
+
+    fn lcd_for(log: &EventLog, variant: ControllerVariant) -> HD44780<mock::Bus> {
+        let mut lcd = lcd(log);
+        lcd.variant = variant;
+        lcd.set_timing(TimingConfig {
+            init: variant.init_timing(),
+            ..TimingConfig::default()
+        });
+        lcd
+    }
+
+    #[test]
+    fn hd44780_variant_keeps_the_default_init() {
+        let log = EventLog::new();
+        lcd_for(&log, ControllerVariant::Hd44780)
+            .init_4bit(&mut log.delay())
+            .unwrap();
+
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(
+            commands(&log),
+            [0x33, 0x32, 0x28, 0x08, 0x01, 0x06, 0x80, on]
+        );
+        assert_eq!(
+            delays(&log),
+            [15000, 5000, 100, 100, 100, 1520, 100, 100, 100]
+        );
+    }
+
+    #[test]
+    fn ks0066_variant_repeats_the_function_set() {
+        let log = EventLog::new();
+        lcd_for(&log, ControllerVariant::Ks0066)
+            .init_4bit(&mut log.delay())
+            .unwrap();
+
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(
+            commands(&log),
+            [0x33, 0x32, 0x28, 0x28, 0x08, 0x01, 0x06, 0x80, on]
+        );
+        assert_eq!(
+            delays(&log),
+            [30000, 5000, 100, 100, 100, 100, 1530, 100, 100, 100]
+        );
+
+        log.clear();
+        lcd_for(&log, ControllerVariant::Ks0066)
+            .init_8bit(&mut log.delay())
+            .unwrap();
+        assert_eq!(commands(&log)[..3], [0b0011_0000, 0b0011_1000, 0b0011_1000]);
+    }
+
+    #[test]
+    fn variant_sets_the_clear_and_home_wait() {
+        let log = EventLog::new();
+        let mut lcd = lcd_for(&log, ControllerVariant::Splc780);
+
+        lcd.clear(&mut log.delay()).unwrap();
+        lcd.reset(&mut log.delay()).unwrap();
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [2000, 2000, 100]);
+    }
+
+    #[test]
+    fn home_waits_for_return_home() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+        lcd.home(&mut log.delay()).unwrap();
+        lcd.reset(&mut log.delay()).unwrap();
+        lcd.set_cursor_pos(0, &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [100, 1520, 1520, 100]);
+        assert_eq!(commands(&log), [0b0000_0010, 0b0000_0010, 0b1000_0000]);
+    }
+
+    #[test]
+    fn clear_waits_for_clear_display() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.clear(&mut log.delay()).unwrap();
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [1520, 100]);
+        assert_eq!(commands(&log), [0b0000_0001]);
+    }
+
+    #[test]
+    fn reinit_recovers_a_scrambled_display() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+        lcd.init_4bit(&mut mock::Delay).unwrap();
+        lcd.set_cursor_visibility(Cursor::Invisible, &mut mock::Delay)
+            .unwrap();
+        lcd.write_str("Hi", &mut mock::Delay).unwrap();
+
+        controller.corrupt();
+        controller.clear_commands();
+        lcd.reinit(&mut mock::Delay).unwrap();
+
+        assert_eq!(controller.commands()[..2], [0x33, 0x32]);
+        assert_eq!(
+            controller.commands().last(),
+            Some(&lcd.display_mode.as_byte())
+        );
+
+        lcd.write_str("ok", &mut mock::Delay).unwrap();
+        let mut line = [0u8; 3];
+        lcd.read_ddram(0x00, &mut line, &mut mock::Delay).unwrap();
+        assert_eq!(&line, b"ok ");
+    }
+
+    #[test]
+    fn reinit_sends_the_display_mode_once() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_cursor_visibility(Cursor::Invisible, &mut log.delay())
+            .unwrap();
+
+        log.clear();
+        lcd.reinit(&mut log.delay()).unwrap();
+
+        let mode = lcd.display_mode().as_byte();
+        let commands = commands(&log);
+        assert_eq!(commands.last(), Some(&mode));
+        assert_eq!(commands.iter().filter(|&&cmd| cmd == mode).count(), 1);
+    }
+
+    #[test]
+    fn reinit_runs_the_set_up_the_display_was_made_with() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.init = Init::EightBit;
+
+        lcd.reinit(&mut log.delay()).unwrap();
+        assert_eq!(commands(&log)[..2], [0b0011_0000, 0b0011_1000]);
+
+        log.clear();
+        lcd.init = Init::Ws0010(true);
+        lcd.reinit(&mut log.delay()).unwrap();
+        assert_eq!(commands(&log)[..2], [0b0011_1000, 0b0000_1000]);
+    }
+
+    /// The DDRAM address `set_cursor_xy` moves to for each of `positions`
+    fn cursor_addresses(size: DisplaySize, positions: &[(u8, u8)]) -> Vec<u8> {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.set_display_size(size).unwrap();
+
+        for &(col, row) in positions {
+            lcd.set_cursor_xy(col, row, &mut log.delay()).unwrap();
+        }
+
+        commands(&log)
+            .into_iter()
+            .map(|cmd| cmd & 0b0111_1111)
+            .collect()
+    }
+
+    #[test]
+    fn cursor_xy_on_a_16x2_display() {
+        assert_eq!(
+            cursor_addresses(DisplaySize::new(16, 2), &[(0, 0), (15, 0), (0, 1), (15, 1)]),
+            [0x00, 0x0F, 0x40, 0x4F]
+        );
+    }
+
+    #[test]
+    fn cursor_xy_on_a_20x4_display() {
+        assert_eq!(
+            cursor_addresses(DisplaySize::new(20, 4), &[(0, 1), (0, 2), (0, 3), (19, 3)]),
+            [0x40, 0x14, 0x54, 0x67]
+        );
+    }
+
+    #[test]
+    fn cursor_xy_on_a_16x4_display() {
+        assert_eq!(
+            cursor_addresses(DisplaySize::new(16, 4), &[(0, 2), (0, 3), (15, 3)]),
+            [0x10, 0x50, 0x5F]
+        );
+    }
+
+    #[test]
+    fn cursor_xy_follows_custom_line_offsets() {
+        // A 16x1 display addressed as two halves
+        let split = DisplaySize::with_line_offsets(8, 2, [0x00, 0x40, 0x00, 0x00]);
+        assert_eq!(
+            cursor_addresses(split, &[(7, 0), (0, 1), (7, 1)]),
+            [0x07, 0x40, 0x47]
+        );
+
+        assert_eq!(
+            cursor_addresses(DisplaySize::new(40, 2), &[(39, 0), (39, 1)]),
+            [0x27, 0x67]
+        );
+    }
+
+    #[test]
+    fn display_size_must_fit_ddram() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        for &size in &[
+            DisplaySize::new(16, 5),
+            DisplaySize::new(40, 4),
+            DisplaySize::new(200, 1),
+            DisplaySize::with_line_offsets(16, 2, [0x00, 0x60, 0x00, 0x00]),
+            DisplaySize::with_line_offsets(20, 2, [0x00, 0x30, 0x00, 0x00]),
+            DisplaySize::with_line_offsets(20, 2, [0x00, 0x1C, 0x00, 0x00]),
+            DisplaySize::with_line_offsets(8, 2, [0x00, 0x3C, 0x00, 0x00]),
+            DisplaySize::new(41, 2),
+            DisplaySize::new(81, 1),
+            DisplaySize::with_line_offsets(8, 1, [0x4C, 0x00, 0x00, 0x00]),
+        ] {
+            assert_eq!(lcd.set_display_size(size), Err(Error::OutOfBounds));
+            assert_eq!(lcd.size(), DisplaySize::default());
+
+            let config = Config {
+                size,
+                ..Config::default()
+            };
+            assert!(matches!(
+                HD44780::from_parts(log.bus(), Init::FourBit, config),
+                Err(Error::OutOfBounds)
+            ));
+        }
+    }
+
+    #[test]
+    fn display_sizes_that_fit_a_line_are_taken() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        for &size in &[
+            DisplaySize::new(40, 2),
+            DisplaySize::new(20, 4),
+            DisplaySize::new(80, 1),
+            DisplaySize::with_line_offsets(8, 2, [0x00, 0x40, 0x00, 0x00]),
+            DisplaySize::with_line_offsets(8, 2, [0x20, 0x60, 0x00, 0x00]),
+        ] {
+            assert_eq!(lcd.set_display_size(size), Ok(()));
+            assert_eq!(lcd.size(), size);
+        }
+    }
+
+    #[test]
+    fn size_is_kept_from_construction() {
+        let log = mock::PinLog::new();
+        let sized = HD44780::new_4bit_with_size(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            DisplaySize::new(20, 4),
+            &mut mock::Delay,
+        )
+        .unwrap();
+        assert_eq!(sized.size(), DisplaySize::new(20, 4));
+
+        let log = EventLog::new();
+        assert_eq!(lcd(&log).size(), DisplaySize::new(16, 2));
+    }
+
+    #[test]
+    fn config_options_combine() {
+        let log = mock::PinLog::new();
+        let lcd = HD44780::new_4bit_with_config(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            Config {
+                size: DisplaySize::new(20, 1),
+                font: FontMode::FiveByTen,
+                ..Config::for_variant(ControllerVariant::Ks0066)
+            },
+            &mut mock::Delay,
+        )
+        .unwrap();
+
+        assert_eq!(lcd.size(), DisplaySize::new(20, 1));
+        assert_eq!(lcd.font, FontMode::FiveByTen);
+        assert_eq!(lcd.variant, ControllerVariant::Ks0066);
+        assert_eq!(lcd.timing.init, ControllerVariant::Ks0066.init_timing());
+    }
+
+    #[test]
+    fn cursor_xy_off_the_display_is_an_error() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        assert_eq!(
+            lcd.set_cursor_xy(16, 0, &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            lcd.set_cursor_xy(0, 2, &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+
+        lcd.set_display_size(DisplaySize::new(20, 4)).unwrap();
+        assert_eq!(
+            lcd.set_cursor_xy(20, 3, &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(commands(&log), []);
+    }
+
+    /// The function set sent during set up for a display of `rows` using
+    /// `font`
+    fn function_set_sent(eight_bit: bool, rows: u8, font: FontMode) -> u8 {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.size = DisplaySize::new(16, rows);
+        lcd.font = font;
+
+        // Past the reset to 4-bit or 8-bit mode
+        if eight_bit {
+            lcd.init_8bit(&mut log.delay()).unwrap();
+            commands(&log)[1]
+        } else {
+            lcd.init_4bit(&mut log.delay()).unwrap();
+            commands(&log)[2]
+        }
+    }
+
+    #[test]
+    fn function_set_follows_the_lines_and_font() {
+        assert_eq!(function_set_sent(false, 2, FontMode::FiveByEight), 0x28);
+        assert_eq!(function_set_sent(false, 1, FontMode::FiveByEight), 0x20);
+        assert_eq!(function_set_sent(false, 1, FontMode::FiveByTen), 0x24);
+        assert_eq!(function_set_sent(true, 2, FontMode::FiveByEight), 0x38);
+        assert_eq!(function_set_sent(true, 1, FontMode::FiveByEight), 0x30);
+        assert_eq!(function_set_sent(true, 1, FontMode::FiveByTen), 0x34);
+    }
+
+    #[test]
+    fn set_lines_resends_the_function_set() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_lines(Lines::One, &mut log.delay()).unwrap();
+        assert_eq!(lcd.size().rows, 1);
+        assert_eq!(
+            lcd.set_cursor_xy(0, 1, &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+
+        lcd.set_font(FontMode::FiveByTen, &mut log.delay()).unwrap();
+        assert_eq!(
+            lcd.set_lines(Lines::Two, &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+
+        lcd.set_font(FontMode::FiveByEight, &mut log.delay())
+            .unwrap();
+        lcd.set_lines(Lines::Two, &mut log.delay()).unwrap();
+        assert_eq!(lcd.size().rows, 2);
+
+        assert_eq!(commands(&log), [0x20, 0x24, 0x20, 0x28]);
+    }
+
+    #[test]
+    fn set_lines_keeps_the_bus_width() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.init = Init::EightBit;
+        lcd.size = DisplaySize::new(20, 4);
+
+        lcd.set_lines(Lines::Two, &mut log.delay()).unwrap();
+        assert_eq!(lcd.size().rows, 4);
+        assert_eq!(
+            lcd.set_font(FontMode::FiveByTen, &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+
+        assert_eq!(commands(&log), [0x38]);
+    }
+
+    #[test]
+    fn failed_set_lines_and_set_font_keep_the_old_setting() {
+        let mut lcd =
+            HD44780::from_parts(mock::Unwritable, Init::FourBit, Config::default()).unwrap();
+
+        assert_eq!(lcd.set_lines(Lines::One, &mut mock::Delay), Err(Error::Bus));
+        assert_eq!(lcd.size().rows, 2);
+
+        lcd.size.rows = 1;
+        assert_eq!(
+            lcd.set_font(FontMode::FiveByTen, &mut mock::Delay),
+            Err(Error::Bus)
+        );
+        assert_eq!(lcd.font, FontMode::FiveByEight);
+    }
+
+    #[test]
+    fn reinit_sends_the_current_function_set() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.init = Init::Ws0010(true);
+        lcd.set_lines(Lines::One, &mut log.delay()).unwrap();
+
+        log.clear();
+        lcd.reinit(&mut log.delay()).unwrap();
+        assert_eq!(commands(&log)[0], 0b0011_0000);
+    }
+
+    #[test]
+    fn five_by_ten_font_needs_one_line() {
+        let log = mock::PinLog::new();
+        let result = HD44780::new_4bit_with_font(
+            log.pin("rs"),
+            log.pin("en"),
+            log.pin("d4"),
+            log.pin("d5"),
+            log.pin("d6"),
+            log.pin("d7"),
+            DisplaySize::new(16, 2),
+            FontMode::FiveByTen,
+            &mut mock::Delay,
+        );
+
+        assert!(matches!(result, Err(Error::Unsupported)));
+        assert_eq!(log.events(), []);
+    }
+
+    #[test]
+    fn set_entry_mode_sends_one_command() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_entry_mode(
+            EntryMode::new(CursorMode::Decrement, ShiftMode::Enabled),
+            &mut log.delay(),
+        )
+        .unwrap();
+
+        assert_eq!(commands(&log), [0b0000_0101]);
+        assert_eq!(lcd.entry_mode().cursor_mode, CursorMode::Decrement);
+    }
+
+    #[test]
+    fn toggles_follow_the_display_mode() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        let last_sent = |log: &EventLog| commands(log).last().copied();
+
+        lcd.init_4bit(&mut log.delay()).unwrap();
+        assert_eq!(last_sent(&log), Some(lcd.display_mode().as_byte()));
+
+        lcd.toggle_cursor_visibility(&mut log.delay()).unwrap();
+        assert_eq!(lcd.display_mode().cursor_visibility, Cursor::Invisible);
+        assert_eq!(last_sent(&log), Some(lcd.display_mode().as_byte()));
+
+        lcd.toggle_display(&mut log.delay()).unwrap();
+        assert_eq!(lcd.display_mode().display, Display::Off);
+        assert_eq!(last_sent(&log), Some(lcd.display_mode().as_byte()));
+
+        lcd.toggle_cursor_visibility(&mut log.delay()).unwrap();
+        lcd.toggle_display(&mut log.delay()).unwrap();
+        assert_eq!(last_sent(&log), Some(lcd.display_mode().as_byte()));
+        assert_eq!(lcd.display_mode(), DisplayMode::default());
+    }
+
+    #[test]
+    fn init_4bit_sends_the_set_up_in_order() {
+        let log = EventLog::new();
+        lcd(&log).init_4bit(&mut log.delay()).unwrap();
 
-        let cmd = self.entry_mode.as_byte();
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(
+            commands(&log),
+            [0x33, 0x32, 0x28, 0x08, 0x01, 0x06, 0x80, on]
+        );
+    }
 
-        self.write_command(cmd, delay)?;
+    #[test]
+    fn init_turns_the_display_on_as_the_display_mode_says() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.display_mode.cursor_blink = CursorBlink::On;
 
-        Ok(())
+        lcd.init_4bit(&mut log.delay()).unwrap();
+
+        assert_eq!(commands(&log).last(), Some(&0b0000_1111));
     }
 
-    /// Set the cursor position
-    ///
-    /// ```rust,ignore
-    /// // Move to line 2
-    /// lcd.set_cursor_pos(40)
-    /// ```
-    pub fn set_cursor_pos<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        position: u8,
-        delay: &mut D,
-    ) -> Result<()> {
-        let lower_7_bits = 0b0111_1111 & position;
+    #[test]
+    fn init_8bit_matches_the_4bit_set_up() {
+        let log = EventLog::new();
+        lcd(&log).init_8bit(&mut log.delay()).unwrap();
 
-        self.write_command(0b1000_0000 | lower_7_bits, delay)?;
+        let on = DisplayMode::default().as_byte();
+        assert_eq!(commands(&log), [0x30, 0x38, 0x08, 0x01, 0x06, 0x80, on]);
+    }
 
-        Ok(())
+    #[test]
+    fn pacing_spaces_out_data_bytes() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_pacing(Some(Duration::from_millis(1)));
+        lcd.write_str("ab", &mut log.delay()).unwrap();
+
+        assert_eq!(
+            log.events(),
+            [
+                Event::Data(b'a'),
+                Event::DelayUs(1000),
+                Event::Data(b'b'),
+                Event::DelayUs(1000),
+            ]
+        );
     }
 
-    /// Shift just the cursor to the left or the right
-    ///
-    /// ```rust,ignore
-    /// lcd.shift_cursor(Direction::Left);
-    /// lcd.shift_cursor(Direction::Right);
-    /// ```
-    pub fn shift_cursor<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        dir: Direction,
-        delay: &mut D,
-    ) -> Result<()> {
-        let bits = match dir {
-            Direction::Left => 0b0000_0000,
-            Direction::Right => 0b0000_0100,
-        };
+    #[test]
+    fn disabling_pacing_restores_full_speed() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        self.write_command(0b0001_0000 | bits | bits, delay)?;
+        lcd.set_pacing(Some(Duration::from_millis(1)));
+        lcd.write_str("ab", &mut log.delay()).unwrap();
+        log.clear();
 
-        Ok(())
+        lcd.set_pacing(None);
+        lcd.write_str("cd", &mut log.delay()).unwrap();
+
+        assert_eq!(delays(&log), [100, 100]);
     }
 
-    /// Shift the entire display to the left or the right
-    ///
-    /// ```rust,ignore
-    /// lcd.shift_display(Direction::Left);
-    /// lcd.shift_display(Direction::Right);
-    /// ```
-    pub fn shift_display<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        dir: Direction,
-        delay: &mut D,
-    ) -> Result<()> {
-        let bits = match dir {
-            Direction::Left => 0b0000_0000,
-            Direction::Right => 0b0000_0100,
-        };
+    #[test]
+    fn pacing_does_not_shorten_the_command_wait() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        self.write_command(0b0001_1000 | bits, delay)?;
+        lcd.set_pacing(Some(Duration::from_micros(10)));
+        lcd.write_str("ab", &mut log.delay()).unwrap();
 
-        Ok(())
+        assert_eq!(delays(&log), [100, 100]);
     }
 
-    /// Write a single character to the `HD44780`. This `char` just gets downcast to a `u8`
-    /// internally, so make sure that whatever character you're printing fits inside that range, or
-    /// you can just use [write_byte](#method.write_byte) to have the compiler check for you.
-    /// See the documentation on that function for more details about compatibility.
-    ///
-    /// ```rust,ignore
-    /// lcd.write_char('A', &mut delay)?; // prints 'A'
-    /// ```
-    pub fn write_char<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        data: char,
-        delay: &mut D,
-    ) -> Result<()> {
-        self.write_byte(data as u8, delay)
+    #[test]
+    fn pacing_saturates_long_gaps() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.set_pacing(Some(Duration::from_secs(1)));
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [1_000_000]);
+
+        log.clear();
+        lcd.set_pacing(Some(Duration::from_secs(10_000)));
+        lcd.write_byte(b'a', &mut log.delay()).unwrap();
+        assert_eq!(delays(&log), [u32::MAX]);
     }
 
-    fn write_command<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        cmd: u8,
-        delay: &mut D,
-    ) -> Result<()> {
-        self.bus.write(cmd, false, delay)?;
+    #[test]
+    fn paced_bytes_counts_the_held_back_bytes() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
-        Ok(())
+        lcd.write_str("ab", &mut log.delay()).unwrap();
+        assert_eq!(lcd.paced_bytes(), 0);
+
+        lcd.set_pacing(Some(Duration::from_micros(10)));
+        lcd.write_str("ab", &mut log.delay()).unwrap();
+        assert_eq!(lcd.paced_bytes(), 0);
+
+        lcd.set_pacing(Some(Duration::from_millis(1)));
+        lcd.write_str("abc", &mut log.delay()).unwrap();
+        assert_eq!(lcd.paced_bytes(), 3);
+
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+        lcd.set_pacing(Some(Duration::ZERO));
+        lcd.write_str("ab", &mut mock::Delay).unwrap();
+        assert_eq!(lcd.paced_bytes(), 0);
+
+        lcd.set_pacing(Some(Duration::from_micros(10)));
+        lcd.write_str("ab", &mut mock::Delay).unwrap();
+        assert_eq!(lcd.paced_bytes(), 2);
     }
 
-    fn init_4bit<D: DelayUs<u16> + DelayMs<u8>>(&mut self, delay: &mut D) -> Result<()> {
-        // Wait for the LCD to wakeup if it was off
-        delay.delay_ms(15u8);
+    #[test]
+    fn busy_flag_is_polled_until_ready() {
+        let controller = Controller::new();
+        controller.set_busy_reads(3);
+        let mut lcd = simulated(&controller);
 
-        // Initialize Lcd in 4-bit mode
-        self.bus.write(0x33, false, delay)?;
+        let log = EventLog::new();
+        lcd.write_str("ab", &mut log.delay()).unwrap();
 
-        // Wait for the command to be processed
-        delay.delay_ms(5u8);
+        // Four reads per byte, backing off after each busy one, and no fixed
+        // wait at all
+        assert_eq!(controller.status_reads(), 8);
+        assert_eq!(delays(&log), [10, 10, 10, 10, 10, 10]);
+    }
 
-        // Sets 4-bit operation and enables 5x7 mode for chars
-        self.bus.write(0x32, false, delay)?;
+    #[test]
+    fn busy_flag_falls_back_to_fixed_delay_on_write_only_bus() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        lcd.set_wait_strategy(WaitStrategy::BusyFlag);
+        lcd.write_str("ab", &mut log.delay()).unwrap();
 
-        self.bus.write(0x28, false, delay)?;
+        assert_eq!(delays(&log), [100, 100]);
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn busy_flag_gives_up_on_a_stuck_display() {
+        let controller = Controller::new();
+        controller.set_busy_reads(u16::MAX);
+        let mut lcd = simulated(&controller);
 
-        // Clear Display
-        self.bus.write(0x0E, false, delay)?;
+        assert_eq!(
+            lcd.write_str("a", &mut mock::Delay),
+            Err(Error::BusyTimeout)
+        );
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn busy_flag_read_errors_are_not_hidden() {
+        let mut lcd =
+            HD44780::from_parts(mock::Unreadable, Init::FourBit, Config::default()).unwrap();
+        lcd.wait_strategy = WaitStrategy::BusyFlag;
 
-        // Move the cursor to beginning of first line
-        self.bus.write(0x01, false, delay)?;
+        assert_eq!(lcd.clear(&mut mock::Delay), Err(Error::Bus));
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn fixed_delay_never_reads() {
+        let controller = Controller::new();
+        controller.set_busy_reads(3);
+        let mut lcd = simulated(&controller);
 
-        // Set entry mode
-        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
+        lcd.set_wait_strategy(WaitStrategy::FixedDelay);
+        lcd.write_str("ab", &mut mock::Delay).unwrap();
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        assert_eq!(controller.status_reads(), 0);
+    }
 
-        self.bus.write(0x80, false, delay)?;
+    #[test]
+    fn read_address_follows_writes() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        let start = lcd.read_address(&mut mock::Delay).unwrap();
+        lcd.write_str("abc", &mut mock::Delay).unwrap();
 
-        Ok(())
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(start + 3));
     }
 
-    // Follow the 8-bit setup procedure as specified in the HD44780 datasheet
-    fn init_8bit<D: DelayUs<u16> + DelayMs<u8>>(&mut self, delay: &mut D) -> Result<()> {
-        // Wait for the LCD to wakeup if it was off
-        delay.delay_ms(15u8);
+    #[test]
+    fn read_address_masks_the_busy_flag() {
+        let controller = Controller::new();
+        controller.set_busy_reads(1);
+        let mut lcd = simulated(&controller);
+        lcd.set_wait_strategy(WaitStrategy::FixedDelay);
 
-        // Initialize Lcd in 8-bit mode
-        self.bus.write(0b0011_0000, false, delay)?;
+        lcd.set_cursor_pos(0x40, &mut mock::Delay).unwrap();
 
-        // Wait for the command to be processed
-        delay.delay_ms(5u8);
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(0x40));
+    }
 
-        // Sets 8-bit operation and enables 5x7 mode for chars
-        self.bus.write(0b0011_1000, false, delay)?;
+    #[test]
+    fn read_address_wraps_to_the_second_line() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        lcd.set_cursor_pos(0x26, &mut mock::Delay).unwrap();
+        lcd.write_str("ab", &mut mock::Delay).unwrap();
 
-        self.bus.write(0b0000_1110, false, delay)?;
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(0x40));
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn read_address_needs_a_readable_bus() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        // Clear Display
-        self.bus.write(0b0000_0001, false, delay)?;
+        assert_eq!(
+            lcd.read_address(&mut log.delay()),
+            Err(Error::ReadUnsupported)
+        );
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn read_ddram_spans_the_line_gap() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
 
-        // Move the cursor to beginning of first line
-        self.bus.write(0b000_0111, false, delay)?;
+        lcd.set_cursor_pos(0x26, &mut mock::Delay).unwrap();
+        lcd.write_str("abcd", &mut mock::Delay).unwrap();
+        lcd.set_cursor_pos(0x05, &mut mock::Delay).unwrap();
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        let mut buf = [0u8; 4];
+        lcd.read_ddram(0x26, &mut buf, &mut mock::Delay).unwrap();
 
-        // Set entry mode
-        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
+        assert_eq!(&buf, b"abcd");
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(0x05));
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    #[test]
+    fn read_ddram_counts_up_when_writes_count_down() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
 
-        Ok(())
+        lcd.write_str("abc", &mut mock::Delay).unwrap();
+        lcd.set_cursor_mode(CursorMode::Decrement, &mut mock::Delay)
+            .unwrap();
+
+        let mut buf = [0u8; 3];
+        lcd.read_ddram(0x00, &mut buf, &mut mock::Delay).unwrap();
+        assert_eq!(&buf, b"abc");
+
+        // Writes still count down afterwards
+        lcd.write_str("x", &mut mock::Delay).unwrap();
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(0x02));
     }
 
-    /// Writes a string to the HD44780. Internally, this just prints the string byte-by-byte, so
-    /// make sure the characters in the string fit in a normal `u8`. See the documentation on
-    /// [write_byte](#method.write_byte) for more details on compatibility.
-    ///
-    /// ```rust,ignore
-    /// lcd.write_str("Hello, World!", &mut delay)?;
-    /// ```
-    pub fn write_str<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        string: &str,
-        delay: &mut D,
-    ) -> Result<()> {
-        self.write_bytes(string.as_bytes(), delay)
+    #[test]
+    fn probe_finds_a_connected_display() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        lcd.write_str("Hi", &mut mock::Delay).unwrap();
+        lcd.set_cursor_pos(PROBE_ADDRESS, &mut mock::Delay).unwrap();
+        lcd.write_byte(b'!', &mut mock::Delay).unwrap();
+        lcd.set_cursor_pos(0x01, &mut mock::Delay).unwrap();
+
+        assert_eq!(lcd.probe(&mut mock::Delay), Ok(true));
+
+        let mut cell = [0u8];
+        lcd.read_ddram(PROBE_ADDRESS, &mut cell, &mut mock::Delay)
+            .unwrap();
+        assert_eq!(cell, *b"!");
+        assert_eq!(lcd.read_address(&mut mock::Delay), Ok(0x01));
     }
 
-    /// Writes a sequence of bytes to the HD44780. See the documentation on the
-    /// [write_byte](#method.write_byte) function for more details about compatibility.
-    ///
-    /// ```rust,ignore
-    /// lcd.write_bytes(b"Hello, World!", &mut delay)?;
-    /// ```
-    pub fn write_bytes<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        string: &[u8],
-        delay: &mut D,
-    ) -> Result<()> {
-        for &b in string {
-            self.write_byte(b, delay)?;
+    #[test]
+    fn probe_restores_the_entry_mode() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+        lcd.set_autoscroll(true, &mut mock::Delay).unwrap();
+        controller.clear_commands();
+
+        assert_eq!(lcd.probe(&mut mock::Delay), Ok(true));
+
+        let commands = controller.commands();
+        assert_eq!(commands[0], 0b0000_0110);
+        assert_eq!(commands[commands.len() - 2], lcd.entry_mode.as_byte());
+    }
+
+    #[test]
+    fn probe_sees_through_a_floating_bus() {
+        let mut lcd =
+            HD44780::from_parts(mock::Floating(0x00), Init::FourBit, Config::default()).unwrap();
+        assert_eq!(lcd.probe(&mut mock::Delay), Ok(false));
+
+        // Pulled up data lines look like a display that is always busy
+        lcd.bus = mock::Floating(0xFF);
+        lcd.set_wait_strategy(WaitStrategy::BusyFlag);
+        assert_eq!(lcd.probe(&mut mock::Delay), Ok(false));
+    }
+
+    #[test]
+    fn probe_needs_a_readable_bus() {
+        let log = EventLog::new();
+
+        assert_eq!(
+            lcd(&log).probe(&mut log.delay()),
+            Err(Error::ReadUnsupported)
+        );
+    }
+
+    #[test]
+    fn read_ddram_needs_a_readable_bus() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        let mut buf = [0u8; 4];
+        assert_eq!(
+            lcd.read_ddram(0x00, &mut buf, &mut log.delay()),
+            Err(Error::ReadUnsupported)
+        );
+    }
+
+    const ARROW: [u8; 8] = [0x04, 0x0E, 0x15, 0x04, 0x04, 0x04, 0x04, 0x00];
+
+    /// Upload `pattern` to CGRAM slot `index` and go back to DDRAM address 0
+    fn upload(lcd: &mut HD44780<mock::ControllerBus>, index: u8, pattern: &[u8; 8]) {
+        lcd.write_command(0b0100_0000 | (index << 3), &mut mock::Delay)
+            .unwrap();
+        lcd.write_bytes(pattern, &mut mock::Delay).unwrap();
+        lcd.set_cursor_pos(0, &mut mock::Delay).unwrap();
+    }
+
+    #[test]
+    fn create_char_writes_the_rows_to_its_slot() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        lcd.create_char(3, ARROW, &mut log.delay()).unwrap();
+
+        let mut expected = std::vec![Event::Command(0b0101_1000)];
+        expected.extend(ARROW.iter().map(|&row| Event::Data(row)));
+        expected.push(Event::Command(0b1000_0000));
+
+        let transfers: Vec<Event> = log
+            .events()
+            .into_iter()
+            .filter(|event| !matches!(event, Event::DelayUs(_)))
+            .collect();
+        assert_eq!(transfers, expected);
+    }
+
+    #[test]
+    fn create_char_restores_the_cursor_when_it_can() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        lcd.set_cursor_pos(0x45, &mut mock::Delay).unwrap();
+        lcd.create_char(2, ARROW, &mut mock::Delay).unwrap();
+        lcd.write_byte(2, &mut mock::Delay).unwrap();
+
+        assert_eq!(lcd.verify_char(2, &ARROW, &mut mock::Delay), Ok(true));
+
+        let mut cell = [0u8];
+        lcd.read_ddram(0x45, &mut cell, &mut mock::Delay).unwrap();
+        assert_eq!(cell, [2]);
+    }
+
+    #[test]
+    fn create_char_takes_eleven_rows_with_the_5x10_font() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+        lcd.font = FontMode::FiveByTen;
+
+        lcd.create_char(1, [0x1F; 11], &mut log.delay()).unwrap();
+        let commands = commands(&log);
+        assert_eq!(commands, [0b0101_0000, 0b1000_0000]);
+
+        assert_eq!(
+            lcd.create_char(4, [0x1F; 11], &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            lcd.create_char(0, ARROW, &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            lcd.load_custom_chars(&[ARROW; 8], &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn create_char_has_only_eight_slots() {
+        let log = EventLog::new();
+
+        assert_eq!(
+            lcd(&log).create_char(8, ARROW, &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(log.events(), []);
+    }
+
+    #[test]
+    fn load_custom_chars_sets_the_address_once() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        let mut bank = [ARROW; 8];
+        for (i, pattern) in bank.iter_mut().enumerate() {
+            pattern[7] = i as u8;
+        }
+
+        lcd.set_cursor_pos(0x03, &mut mock::Delay).unwrap();
+        controller.clear_commands();
+        lcd.load_custom_chars(&bank, &mut mock::Delay).unwrap();
+
+        assert_eq!(controller.commands(), [0b0100_0000, 0b1000_0011]);
+        for (i, pattern) in bank.iter().enumerate() {
+            assert_eq!(
+                lcd.verify_char(i as u8, pattern, &mut mock::Delay),
+                Ok(true)
+            );
         }
-        Ok(())
     }
 
-    /// Writes a single byte to the HD44780. These usually map to ASCII characters when printed on the
-    /// screen, but not always. While it varies depending on the ROM of the LCD, `0x20u8..=0x5b`
-    /// and `0x5d..=0x7d` should map to their standard ASCII characters. That is, all the printable
-    /// ASCII characters work, excluding `\` and `~`, which are usually displayed as `¥` and `🡢`
-    /// respectively.
-    ///
-    /// More information can be found in the Hitachi datasheets for the HD44780.
-    ///
-    /// ```rust,ignore
-    /// lcd.write_byte(b'A', &mut delay)?; // prints 'A'
-    /// lcd.write_byte(b'\\', &mut delay)?; // usually prints ¥
-    /// lcd.write_byte(b'~', &mut delay)?; // usually prints 🡢
-    /// lcd.write_byte(b'\x7f', &mut delay)?; // usually prints 🡠
-    /// ```
-    pub fn write_byte<D: DelayUs<u16> + DelayMs<u8>>(
-        &mut self,
-        data: u8,
-        delay: &mut D
-    ) -> Result<()> {
-        self.bus.write(data, true, delay)?;
+    #[test]
+    fn load_custom_chars_partial_fills_the_first_slots() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        lcd.load_custom_chars_partial(&[ARROW, ARROW], &mut log.delay())
+            .unwrap();
 
-        Ok(())
+        let commands = commands(&log);
+        assert_eq!(commands, [0b0100_0000, 0b1000_0000]);
+        let rows = log
+            .events()
+            .into_iter()
+            .filter(|event| matches!(event, Event::Data(_)))
+            .count();
+        assert_eq!(rows, 16);
     }
 
-    // Pulse the enable pin telling the HD44780 that we something for it
-    /*fn pulse_enable(&mut self) {
-        self.en.set_high();
-        self.delay.delay_ms(15u8);
-        self.en.set_low();
-    }*/
-}
+    #[test]
+    fn load_custom_chars_partial_rejects_more_than_eight() {
+        let log = EventLog::new();
 
-//impl<B> Write for HD44780<B>
-//where
-//    B: DataBus,
-//{
-//    fn write_str(&mut self, string: &str) -> Result {
-//        for c in string.chars() {
-//            self.write_char(c, delay);
-//        }
-//        Ok(())
-//    }
-//}
+        assert_eq!(
+            lcd(&log).load_custom_chars_partial(&[ARROW; 9], &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(log.events(), []);
+    }
+
+    #[test]
+    fn read_cgram_restores_the_ddram_address() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        upload(&mut lcd, 3, &ARROW);
+        lcd.set_cursor_pos(0x45, &mut mock::Delay).unwrap();
+        controller.clear_commands();
+
+        let mut rows = [0u8; 8];
+        lcd.read_cgram(3, &mut rows, &mut mock::Delay).unwrap();
+
+        assert_eq!(rows, ARROW);
+        assert_eq!(controller.commands(), [0b0101_1000, 0b1100_0101]);
+    }
+
+    #[test]
+    fn verify_char_spots_a_missed_upload() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        upload(&mut lcd, 0, &ARROW);
+
+        assert_eq!(lcd.verify_char(0, &ARROW, &mut mock::Delay), Ok(true));
+        assert_eq!(lcd.verify_char(1, &ARROW, &mut mock::Delay), Ok(false));
+    }
+
+    #[test]
+    fn verify_char_ignores_the_unused_bits() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        upload(&mut lcd, 0, &ARROW);
+
+        let mut marked = ARROW;
+        marked[0] |= 0b1110_0000;
+        assert_eq!(lcd.verify_char(0, &marked, &mut mock::Delay), Ok(true));
+    }
+
+    #[test]
+    fn read_cgram_rejects_slots_past_the_last() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+
+        let mut rows = [0u8; 8];
+        assert_eq!(
+            lcd.read_cgram(8, &mut rows, &mut mock::Delay),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            lcd.verify_char(8, &ARROW, &mut mock::Delay),
+            Err(Error::OutOfBounds)
+        );
+
+        lcd.font = FontMode::FiveByTen;
+        let mut rows = [0u8; 11];
+        assert_eq!(
+            lcd.read_cgram(4, &mut rows, &mut mock::Delay),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(controller.commands(), []);
+    }
+
+    #[test]
+    fn read_cgram_addresses_5x10_characters() {
+        let controller = Controller::new();
+        let mut lcd = simulated(&controller);
+        lcd.font = FontMode::FiveByTen;
+
+        let tall = [
+            0x1F, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1F,
+        ];
+        lcd.create_char(1, tall, &mut mock::Delay).unwrap();
+        controller.clear_commands();
+
+        let mut rows = [0u8; 11];
+        lcd.read_cgram(1, &mut rows, &mut mock::Delay).unwrap();
+        assert_eq!(rows, tall);
+        assert_eq!(controller.commands()[0], 0b0101_0000);
+
+        assert_eq!(lcd.verify_char(1, &tall, &mut mock::Delay), Ok(true));
+        assert_eq!(
+            lcd.verify_char(1, &ARROW, &mut mock::Delay),
+            Err(Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn read_cgram_needs_a_readable_bus() {
+        let log = EventLog::new();
+        let mut lcd = lcd(&log);
+
+        let mut rows = [0u8; 8];
+        assert_eq!(
+            lcd.read_cgram(0, &mut rows, &mut log.delay()),
+            Err(Error::ReadUnsupported)
+        );
+    }
+}
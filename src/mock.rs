@@ -0,0 +1,518 @@
+//! Test doubles for exercising the buses and the driver without hardware
+
+extern crate std;
+
+use core::convert::Infallible;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+use std::vec::Vec;
+
+use crate::delay::DelayNs;
+use crate::digital::{InputPin, OutputPin};
+use embedded_hal_0_2::blocking::i2c;
+
+use crate::bus::DataBus;
+use crate::error::{Error, Result};
+
+/// A shared, ordered record of every level change made through the pins
+/// handed out by it
+#[derive(Clone, Default)]
+pub struct PinLog(Rc<RefCell<PinState>>);
+
+#[derive(Default)]
+struct PinState {
+    events: Vec<(&'static str, bool)>,
+    levels: BTreeMap<&'static str, bool>,
+    inputs: BTreeMap<&'static str, bool>,
+    responses: VecDeque<Response>,
+}
+
+/// A value the display puts on the data pins when it is read
+struct Response {
+    rw: &'static str,
+    en: &'static str,
+    data: Vec<&'static str>,
+    value: u8,
+}
+
+impl PinLog {
+    pub fn new() -> PinLog {
+        PinLog::default()
+    }
+
+    pub fn pin(&self, name: &'static str) -> Pin {
+        Pin {
+            name,
+            log: self.clone(),
+        }
+    }
+
+    pub fn events(&self) -> Vec<(&'static str, bool)> {
+        self.0.borrow().events.clone()
+    }
+
+    /// Drive `values` onto the `data` pins (least significant first), one
+    /// for each rising edge of `en` while `rw` is high
+    pub fn respond(
+        &self,
+        rw: &'static str,
+        en: &'static str,
+        data: &[&'static str],
+        values: &[u8],
+    ) {
+        let mut state = self.0.borrow_mut();
+
+        for &value in values {
+            state.responses.push_back(Response {
+                rw,
+                en,
+                data: data.to_vec(),
+                value,
+            });
+        }
+    }
+
+    /// Sample `rs` and the `data` pins (least significant first) on every
+    /// falling edge of `en`, the way the controller latches them
+    pub fn latched(&self, en: &str, rs: &str, data: &[&str]) -> Vec<(bool, u8)> {
+        let mut levels: BTreeMap<&str, bool> = BTreeMap::new();
+        let mut latched = Vec::new();
+
+        for &(name, level) in self.0.borrow().events.iter() {
+            let was_high = levels.get(name).copied().unwrap_or(false);
+            levels.insert(name, level);
+
+            if name == en && was_high && !level {
+                let value = data.iter().enumerate().fold(0u8, |acc, (i, pin)| {
+                    acc | ((levels.get(pin).copied().unwrap_or(false) as u8) << i)
+                });
+
+                latched.push((levels.get(rs).copied().unwrap_or(false), value));
+            }
+        }
+
+        latched
+    }
+
+    /// Pair up the nibbles latched on a 4-bit bus into `(rs, byte)` transfers
+    pub fn bytes_4bit(&self, en: &str, rs: &str, data: [&str; 4]) -> Vec<(bool, u8)> {
+        self.latched(en, rs, &data)
+            .chunks(2)
+            .map(|pair| (pair[0].0, (pair[0].1 << 4) | pair[1].1))
+            .collect()
+    }
+}
+
+pub struct Pin {
+    name: &'static str,
+    log: PinLog,
+}
+
+impl Pin {
+    fn set(&mut self, level: bool) {
+        let mut state = self.log.0.borrow_mut();
+        state.events.push((self.name, level));
+        state.levels.insert(self.name, level);
+
+        let respond = match state.responses.front() {
+            Some(response) => {
+                level
+                    && response.en == self.name
+                    && state.levels.get(response.rw).copied().unwrap_or(false)
+            }
+            None => false,
+        };
+
+        if respond {
+            let response = state.responses.pop_front().unwrap();
+            for (i, &pin) in response.data.iter().enumerate() {
+                state.inputs.insert(pin, response.value & (1 << i) != 0);
+            }
+        }
+    }
+}
+
+impl embedded_hal::digital::ErrorType for Pin {
+    type Error = Infallible;
+}
+
+impl OutputPin for Pin {
+    fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+        self.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+        self.set(true);
+        Ok(())
+    }
+}
+
+impl InputPin for Pin {
+    fn is_high(&mut self) -> core::result::Result<bool, Infallible> {
+        Ok(self.level())
+    }
+
+    fn is_low(&mut self) -> core::result::Result<bool, Infallible> {
+        Ok(!self.level())
+    }
+}
+
+impl Pin {
+    /// The level the simulated display is driving this pin to
+    fn level(&self) -> bool {
+        self.log
+            .0
+            .borrow()
+            .inputs
+            .get(self.name)
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+/// A data pin that drives its output like a [Pin](struct.Pin.html) but fails
+/// every read, like a GPIO expander that has stopped answering
+pub struct BrokenInput(pub Pin);
+
+impl embedded_hal::digital::ErrorType for BrokenInput {
+    type Error = embedded_hal::digital::ErrorKind;
+}
+
+impl OutputPin for BrokenInput {
+    fn set_low(&mut self) -> core::result::Result<(), Self::Error> {
+        self.0.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> core::result::Result<(), Self::Error> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+impl InputPin for BrokenInput {
+    fn is_high(&mut self) -> core::result::Result<bool, Self::Error> {
+        Err(embedded_hal::digital::ErrorKind::Other)
+    }
+
+    fn is_low(&mut self) -> core::result::Result<bool, Self::Error> {
+        Err(embedded_hal::digital::ErrorKind::Other)
+    }
+}
+
+/// A delay that returns immediately
+pub struct Delay;
+
+impl DelayNs for Delay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// A byte transferred to the controller, or a wait between transfers
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Event {
+    Command(u8),
+    Data(u8),
+    DelayUs(u32),
+}
+
+/// A shared, ordered record of the bytes written through a mock
+/// [Bus](struct.Bus.html) and the waits made by a [TimedDelay](struct.TimedDelay.html)
+#[derive(Clone, Default)]
+pub struct EventLog(Rc<RefCell<Vec<Event>>>);
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog::default()
+    }
+
+    pub fn bus(&self) -> Bus {
+        Bus { log: self.clone() }
+    }
+
+    pub fn delay(&self) -> TimedDelay {
+        TimedDelay { log: self.clone() }
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.0.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+/// A bus that records every byte written to it instead of driving pins
+pub struct Bus {
+    log: EventLog,
+}
+
+impl DataBus for Bus {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, _delay: &mut D) -> Result<()> {
+        let event = if data {
+            Event::Data(byte)
+        } else {
+            Event::Command(byte)
+        };
+        self.log.0.borrow_mut().push(event);
+
+        Ok(())
+    }
+}
+
+/// A delay that records how long it was asked to wait instead of waiting
+pub struct TimedDelay {
+    log: EventLog,
+}
+
+impl DelayNs for TimedDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay_us(ns.div_ceil(1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.log.0.borrow_mut().push(Event::DelayUs(us));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.log.0.borrow_mut().push(Event::DelayUs(ms * 1000));
+    }
+}
+
+/// A shared, ordered record of the writes made through a mock
+/// [I2c](struct.I2c.html) peripheral as `(address, bytes)`
+#[derive(Clone, Default)]
+pub struct I2cLog(Rc<RefCell<Vec<(u8, Vec<u8>)>>>);
+
+impl I2cLog {
+    pub fn new() -> I2cLog {
+        I2cLog::default()
+    }
+
+    pub fn i2c(&self) -> I2c {
+        I2c { log: self.clone() }
+    }
+
+    pub fn writes(&self) -> Vec<(u8, Vec<u8>)> {
+        self.0.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+/// An I2C peripheral that records every write instead of sending it
+pub struct I2c {
+    log: I2cLog,
+}
+
+impl i2c::Write for I2c {
+    type Error = Infallible;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> core::result::Result<(), Infallible> {
+        self.log.0.borrow_mut().push((address, bytes.to_vec()));
+        Ok(())
+    }
+}
+
+/// A simulated controller that decodes the instructions written to it,
+/// keeping DDRAM, CGRAM and the address counter the way a two line HD44780
+/// does
+#[derive(Clone)]
+pub struct Controller(Rc<RefCell<ControllerState>>);
+
+struct ControllerState {
+    ddram: [u8; 0x80],
+    cgram: [u8; 0x40],
+    address: u8,
+    cgram_selected: bool,
+    increment: bool,
+    busy_reads: u16,
+    busy_left: u16,
+    status_reads: usize,
+    commands: Vec<u8>,
+}
+
+impl Controller {
+    pub fn new() -> Controller {
+        Controller(Rc::new(RefCell::new(ControllerState {
+            ddram: [b' '; 0x80],
+            cgram: [0; 0x40],
+            address: 0,
+            cgram_selected: false,
+            increment: true,
+            busy_reads: 0,
+            busy_left: 0,
+            status_reads: 0,
+            commands: Vec::new(),
+        })))
+    }
+
+    pub fn bus(&self) -> ControllerBus {
+        ControllerBus {
+            controller: self.clone(),
+        }
+    }
+
+    /// Report busy for this many busy flag reads after every transfer
+    pub fn set_busy_reads(&self, reads: u16) {
+        self.0.borrow_mut().busy_reads = reads;
+    }
+
+    /// How many times the busy flag and address counter have been read
+    pub fn status_reads(&self) -> usize {
+        self.0.borrow().status_reads
+    }
+
+    /// Every instruction written since the last
+    /// [clear_commands](#method.clear_commands), in order
+    pub fn commands(&self) -> Vec<u8> {
+        self.0.borrow().commands.clone()
+    }
+
+    pub fn clear_commands(&self) {
+        self.0.borrow_mut().commands.clear();
+    }
+
+    /// Scramble DDRAM, the address counter and the entry mode, the way a
+    /// supply glitch might
+    pub fn corrupt(&self) {
+        let mut state = self.0.borrow_mut();
+        state.ddram = [0xFF; 0x80];
+        state.address = 0x15;
+        state.cgram_selected = true;
+        state.increment = false;
+    }
+}
+
+impl ControllerState {
+    fn instruction(&mut self, cmd: u8) {
+        self.commands.push(cmd);
+
+        if cmd & 0b1000_0000 != 0 {
+            self.cgram_selected = false;
+            self.address = cmd & 0b0111_1111;
+        } else if cmd & 0b0100_0000 != 0 {
+            self.cgram_selected = true;
+            self.address = cmd & 0b0011_1111;
+        } else if cmd & 0b1111_1100 == 0b0000_0100 {
+            self.increment = cmd & 0b0000_0010 != 0;
+        } else if cmd & 0b1111_1110 == 0b0000_0010 {
+            self.cgram_selected = false;
+            self.address = 0;
+        } else if cmd == 0b0000_0001 {
+            self.ddram = [b' '; 0x80];
+            self.cgram_selected = false;
+            self.address = 0;
+            self.increment = true;
+        }
+    }
+
+    /// Move the address counter along after a data transfer, jumping over
+    /// the gap between the two lines of DDRAM
+    fn advance(&mut self) {
+        self.address = match (self.cgram_selected, self.increment, self.address) {
+            (true, true, a) => (a + 1) & 0x3F,
+            (true, false, a) => a.wrapping_sub(1) & 0x3F,
+            (false, true, 0x27) => 0x40,
+            (false, true, 0x67) => 0x00,
+            (false, true, a) => a + 1,
+            (false, false, 0x40) => 0x27,
+            (false, false, 0x00) => 0x67,
+            (false, false, a) => a - 1,
+        };
+    }
+}
+
+/// A bus to a simulated [Controller](struct.Controller.html) that can be read
+pub struct ControllerBus {
+    controller: Controller,
+}
+
+impl DataBus for ControllerBus {
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, _delay: &mut D) -> Result<()> {
+        let mut state = self.controller.0.borrow_mut();
+
+        if data {
+            let address = state.address as usize;
+            if state.cgram_selected {
+                state.cgram[address] = byte;
+            } else {
+                state.ddram[address] = byte;
+            }
+            state.advance();
+        } else {
+            state.instruction(byte);
+        }
+
+        state.busy_left = state.busy_reads;
+
+        Ok(())
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, _delay: &mut D) -> Result<u8> {
+        let mut state = self.controller.0.borrow_mut();
+
+        if data {
+            let address = state.address as usize;
+            let byte = if state.cgram_selected {
+                state.cgram[address]
+            } else {
+                state.ddram[address]
+            };
+            state.advance();
+            state.busy_left = state.busy_reads;
+
+            return Ok(byte);
+        }
+
+        state.status_reads += 1;
+
+        if state.busy_left > 0 {
+            state.busy_left -= 1;
+            Ok(0b1000_0000 | state.address)
+        } else {
+            Ok(state.address)
+        }
+    }
+}
+
+/// A bus that accepts every write but fails every read, like a display whose
+/// data lines have come loose
+pub struct Unreadable;
+
+impl DataBus for Unreadable {
+    fn write<D: DelayNs>(&mut self, _byte: u8, _data: bool, _delay: &mut D) -> Result<()> {
+        Ok(())
+    }
+
+    fn read<D: DelayNs>(&mut self, _data: bool, _delay: &mut D) -> Result<u8> {
+        Err(Error::Bus)
+    }
+}
+
+/// A bus that fails every write
+pub struct Unwritable;
+
+impl DataBus for Unwritable {
+    fn write<D: DelayNs>(&mut self, _byte: u8, _data: bool, _delay: &mut D) -> Result<()> {
+        Err(Error::Bus)
+    }
+}
+
+/// A bus with no display on the end of it, where every read sees the data
+/// lines float to `level`
+pub struct Floating(pub u8);
+
+impl DataBus for Floating {
+    fn write<D: DelayNs>(&mut self, _byte: u8, _data: bool, _delay: &mut D) -> Result<()> {
+        Ok(())
+    }
+
+    fn read<D: DelayNs>(&mut self, _data: bool, _delay: &mut D) -> Result<u8> {
+        Ok(self.0)
+    }
+}
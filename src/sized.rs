@@ -0,0 +1,256 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::delay::DelayNs;
+
+use crate::bus::DataBus;
+use crate::error::{Error, Result};
+use crate::{DisplaySize, Lines, HD44780};
+
+/// A [HD44780](../struct.HD44780.html) whose size is part of its type, so
+/// rows can be handled as `[u8; COLS]` arrays with no size kept at runtime.
+///
+/// Everything that can be done with a `HD44780` can be done through this
+/// too, apart from changing the number of rows or columns, which
+/// [set_display_size](#method.set_display_size) and
+/// [set_lines](#method.set_lines) refuse. For displays whose size is only
+/// known at runtime, keep using `HD44780` with a
+/// [DisplaySize](../struct.DisplaySize.html).
+///
+/// ```rust,ignore
+/// let mut lcd: SizedHd44780<_, 20, 4> = HD44780::new_4bit(rs, en, d4, d5, d6, d7, &mut delay)?.into();
+/// lcd.write_row(3, b"Bottom row          ", &mut delay)?;
+/// ```
+pub struct SizedHd44780<B: DataBus, const COLS: usize, const ROWS: usize> {
+    lcd: HD44780<B>,
+}
+
+impl<B: DataBus, const COLS: usize, const ROWS: usize> SizedHd44780<B, COLS, ROWS> {
+    /// The size of the display, as a runtime `DisplaySize`
+    pub const SIZE: DisplaySize = DisplaySize::new(COLS as u8, ROWS as u8);
+
+    const FITS: () = assert!(
+        COLS > 0
+            && match ROWS {
+                1 => COLS <= 80,
+                2 => COLS <= 40,
+                3 | 4 => COLS <= 20,
+                _ => false,
+            },
+        "a HD44780 drives one row of up to 80, two of up to 40, or up to 4 of up to 20"
+    );
+
+    /// Wrap `lcd`, setting its size to `COLS` by `ROWS`
+    pub fn new(mut lcd: HD44780<B>) -> SizedHd44780<B, COLS, ROWS> {
+        let () = Self::FITS;
+
+        // FITS keeps every row of SIZE within DDRAM
+        lcd.size = Self::SIZE;
+        SizedHd44780 { lcd }
+    }
+
+    /// Give back the `HD44780`, which keeps the size
+    pub fn into_inner(self) -> HD44780<B> {
+        self.lcd
+    }
+
+    /// Change the row addresses, for modules wired differently from the
+    /// usual. A size of other than `COLS` by `ROWS` returns
+    /// `Error::Unsupported` and keeps the size as it was.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_display_size(DisplaySize::with_line_offsets(8, 2, [0x00, 0x40, 0x00, 0x00]))?;
+    /// ```
+    pub fn set_display_size(&mut self, size: DisplaySize) -> Result<()> {
+        if size.cols as usize != COLS || size.rows as usize != ROWS {
+            return Err(Error::Unsupported);
+        }
+
+        self.lcd.set_display_size(size)
+    }
+
+    /// Send the function set again, for the one line mode with one row and
+    /// two line mode otherwise. The other mode would change the number of
+    /// rows, so returns `Error::Unsupported` without sending anything.
+    pub fn set_lines<D: DelayNs>(&mut self, lines: Lines, delay: &mut D) -> Result<()> {
+        let fits = match lines {
+            Lines::One => ROWS == 1,
+            Lines::Two => ROWS > 1,
+        };
+        if !fits {
+            return Err(Error::Unsupported);
+        }
+
+        self.lcd.set_lines(lines, delay)
+    }
+
+    /// Write `text` starting at `col` on `row`, returning
+    /// `Error::OutOfBounds` if it would run off the end of the row
+    ///
+    /// ```rust,ignore
+    /// lcd.write_str_at(14, 0, "12:00", &mut delay)?;
+    /// ```
+    pub fn write_str_at<D: DelayNs>(
+        &mut self,
+        col: usize,
+        row: usize,
+        text: &str,
+        delay: &mut D,
+    ) -> Result<()> {
+        if row >= ROWS || col + text.len() > COLS {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.lcd.set_cursor_xy(col as u8, row as u8, delay)?;
+        self.lcd.write_str(text, delay)
+    }
+
+    /// Replace the whole of `row` with `line`
+    ///
+    /// ```rust,ignore
+    /// lcd.write_row(1, b"Temp 21C        ", &mut delay)?;
+    /// ```
+    pub fn write_row<D: DelayNs>(
+        &mut self,
+        row: usize,
+        line: &[u8; COLS],
+        delay: &mut D,
+    ) -> Result<()> {
+        if row >= ROWS {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.lcd.set_cursor_xy(0, row as u8, delay)?;
+        self.lcd.write_bytes(line, delay)
+    }
+
+    /// Blank `row`, leaving the cursor at its start
+    ///
+    /// ```rust,ignore
+    /// lcd.clear_row(1, &mut delay)?;
+    /// lcd.write_str("Done", &mut delay)?;
+    /// ```
+    pub fn clear_row<D: DelayNs>(&mut self, row: usize, delay: &mut D) -> Result<()> {
+        self.write_row(row, &[b' '; COLS], delay)?;
+        self.lcd.set_cursor_xy(0, row as u8, delay)
+    }
+}
+
+impl<B: DataBus, const COLS: usize, const ROWS: usize> From<HD44780<B>>
+    for SizedHd44780<B, COLS, ROWS>
+{
+    fn from(lcd: HD44780<B>) -> SizedHd44780<B, COLS, ROWS> {
+        SizedHd44780::new(lcd)
+    }
+}
+
+impl<B: DataBus, const COLS: usize, const ROWS: usize> Deref for SizedHd44780<B, COLS, ROWS> {
+    type Target = HD44780<B>;
+
+    fn deref(&self) -> &HD44780<B> {
+        &self.lcd
+    }
+}
+
+impl<B: DataBus, const COLS: usize, const ROWS: usize> DerefMut for SizedHd44780<B, COLS, ROWS> {
+    fn deref_mut(&mut self) -> &mut HD44780<B> {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{self, Event, EventLog};
+    use crate::{Config, Init};
+
+    fn lcd<const COLS: usize, const ROWS: usize>(
+        log: &EventLog,
+    ) -> SizedHd44780<mock::Bus, COLS, ROWS> {
+        HD44780::from_parts(log.bus(), Init::FourBit, Config::default())
+            .unwrap()
+            .into()
+    }
+
+    fn transfers(log: &EventLog) -> Vec<Event> {
+        log.events()
+            .into_iter()
+            .filter(|event| !matches!(event, Event::DelayUs(_)))
+            .collect()
+    }
+
+    #[test]
+    fn size_comes_from_the_type() {
+        let log = EventLog::new();
+        let lcd: SizedHd44780<_, 20, 4> = lcd(&log);
+
+        assert_eq!(lcd.size(), DisplaySize::new(20, 4));
+        assert_eq!(lcd.into_inner().size(), DisplaySize::new(20, 4));
+    }
+
+    #[test]
+    fn write_str_at_checks_the_row_length() {
+        let log = EventLog::new();
+        let mut lcd: SizedHd44780<_, 20, 4> = lcd(&log);
+
+        lcd.write_str_at(18, 2, "Hi", &mut log.delay()).unwrap();
+        assert_eq!(
+            transfers(&log),
+            [
+                Event::Command(0x80 | 0x26),
+                Event::Data(b'H'),
+                Event::Data(b'i')
+            ]
+        );
+
+        log.clear();
+        assert_eq!(
+            lcd.write_str_at(19, 2, "Hi", &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            lcd.write_str_at(0, 4, "Hi", &mut log.delay()),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(transfers(&log), []);
+    }
+
+    #[test]
+    fn size_can_not_be_changed_through_the_wrapper() {
+        let log = EventLog::new();
+        let mut lcd: SizedHd44780<_, 16, 2> = lcd(&log);
+
+        assert_eq!(
+            lcd.set_display_size(DisplaySize::new(20, 4)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            lcd.set_lines(Lines::One, &mut log.delay()),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(lcd.size(), DisplaySize::new(16, 2));
+        assert_eq!(transfers(&log), []);
+
+        let split = DisplaySize::with_line_offsets(16, 2, [0x00, 0x50, 0x00, 0x00]);
+        lcd.set_display_size(split).unwrap();
+        lcd.set_lines(Lines::Two, &mut log.delay()).unwrap();
+        assert_eq!(lcd.size(), split);
+    }
+
+    #[test]
+    fn clear_row_blanks_a_whole_row() {
+        let log = EventLog::new();
+        let mut lcd: SizedHd44780<_, 16, 2> = lcd(&log);
+
+        lcd.clear_row(1, &mut log.delay()).unwrap();
+
+        let transfers = transfers(&log);
+        assert_eq!(transfers.len(), 18);
+        assert_eq!(transfers[0], Event::Command(0xC0));
+        assert!(transfers[1..17].iter().all(|&e| e == Event::Data(b' ')));
+        assert_eq!(transfers[17], Event::Command(0xC0));
+    }
+}
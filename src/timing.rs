@@ -0,0 +1,172 @@
+use crate::delay::DelayNs;
+
+/// How long the driver waits for the `HD44780` during set up, after each
+/// instruction and while strobing the enable pin.
+///
+/// The default matches the timing the driver has always used. Clone
+/// controllers can need two to three times as long, see
+/// [relaxed](#method.relaxed), while genuine HD44780U parts run well with
+/// the datasheet minimums, see [fast](#method.fast).
+///
+/// ```rust,ignore
+/// let mut lcd = HD44780::new_4bit_with_timing(
+///     rs, en, d4, d5, d6, d7,
+///     TimingConfig::relaxed(),
+///     &mut delay,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingConfig {
+    /// The waits while setting the display up, see
+    /// [InitTiming](struct.InitTiming.html)
+    pub init: InitTiming,
+    /// Time in microseconds to wait after each instruction or data byte
+    /// when the busy flag can't be read
+    pub command_us: u16,
+    /// Time in microseconds to hold the enable pin high for, or `None` for
+    /// the bus's own pulse. Only the parallel GPIO buses use this.
+    pub enable_pulse_us: Option<u16>,
+}
+
+/// How long the driver waits for the `HD44780` while setting it up, apart
+/// from the steady state instruction wait in
+/// [TimingConfig](struct.TimingConfig.html).
+///
+/// ```rust,ignore
+/// let timing = TimingConfig {
+///     init: InitTiming::conservative(),
+///     ..TimingConfig::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitTiming {
+    /// Time in milliseconds to wait for the display to power up before the
+    /// first instruction
+    pub power_on_ms: u8,
+    /// Time in milliseconds to wait after the first function set
+    pub function_set_ms: u8,
+    /// Time in microseconds to wait after clearing the display. The driver
+    /// never waits less than the controller variant takes to clear.
+    pub clear_us: u16,
+}
+
+impl Default for InitTiming {
+    fn default() -> InitTiming {
+        InitTiming {
+            power_on_ms: 15,
+            function_set_ms: 5,
+            clear_us: 1520,
+        }
+    }
+}
+
+impl InitTiming {
+    /// Waits long enough for the slowest clone controllers, such as the
+    /// SPLC780D, that fail to initialize now and then with the default
+    /// timing: 50 ms for power up (the HD44780U needs 40 ms at 2.7 V), 10 ms
+    /// after the first function set and the full 2 ms a clear can take.
+    pub fn conservative() -> InitTiming {
+        InitTiming {
+            power_on_ms: 50,
+            function_set_ms: 10,
+            clear_us: 2000,
+        }
+    }
+}
+
+impl Default for TimingConfig {
+    fn default() -> TimingConfig {
+        TimingConfig {
+            init: InitTiming::default(),
+            command_us: 100,
+            enable_pulse_us: None,
+        }
+    }
+}
+
+impl TimingConfig {
+    /// Three times the default waits, for clone controllers that drop
+    /// instructions or fail to initialize with the default timing
+    pub fn relaxed() -> TimingConfig {
+        TimingConfig {
+            init: InitTiming {
+                power_on_ms: 45,
+                function_set_ms: 15,
+                clear_us: 4560,
+            },
+            command_us: 300,
+            enable_pulse_us: None,
+        }
+    }
+
+    /// The HD44780U datasheet minimums, rounded up, for genuine parts
+    pub fn fast() -> TimingConfig {
+        TimingConfig {
+            init: InitTiming::default(),
+            command_us: 40,
+            enable_pulse_us: Some(1),
+        }
+    }
+
+    /// Time in microseconds the enable pin is held high for
+    pub(crate) fn enable_pulse_len_us(&self) -> u32 {
+        match self.enable_pulse_us {
+            Some(us) => u32::from(us),
+            None => 2000,
+        }
+    }
+
+    /// Hold the enable pin high for the configured pulse
+    pub(crate) fn enable_pulse<D: DelayNs>(&self, delay: &mut D) {
+        match self.enable_pulse_us {
+            Some(us) => delay.delay_us(u32::from(us)),
+            None => delay.delay_ms(2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::bus::{DataBus, FourBitBus};
+    use crate::mock::{Event, EventLog, PinLog};
+
+    fn pulses(timing: &TimingConfig) -> Vec<u32> {
+        let pins = PinLog::new();
+        let log = EventLog::new();
+        let mut bus = FourBitBus::from_pins(
+            pins.pin("rs"),
+            pins.pin("en"),
+            pins.pin("d4"),
+            pins.pin("d5"),
+            pins.pin("d6"),
+            pins.pin("d7"),
+        );
+        bus.set_timing(timing);
+
+        bus.write(0x41, true, &mut log.delay()).unwrap();
+
+        log.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::DelayUs(us) => Some(us),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enable_pulse_defaults_to_the_bus_pulse() {
+        assert_eq!(pulses(&TimingConfig::default()), [2000, 2000]);
+        assert_eq!(pulses(&TimingConfig::relaxed()), [2000, 2000]);
+    }
+
+    #[test]
+    fn fast_timing_shortens_the_enable_pulse() {
+        assert_eq!(pulses(&TimingConfig::fast()), [1, 1]);
+    }
+}